@@ -0,0 +1,56 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use clap::crate_name;
+use dirs::cache_dir;
+use tokio::fs;
+
+use crate::errors::TResult;
+
+/// Raw API responses kept on disk, one file per endpoint.
+/// The file's modification time is what decides how old an entry is.
+#[derive(Debug, Clone)]
+pub(super) struct ResponseCache {
+    dir: PathBuf,
+}
+
+pub(super) struct CachedResponse {
+    pub(super) body: String,
+    pub(super) age: Duration,
+}
+
+impl ResponseCache {
+    /// `None` when there's no cache folder on this system
+    pub(super) fn new() -> Option<Self> {
+        Some(Self {
+            dir: cache_dir()?.join(crate_name!()).join("api"),
+        })
+    }
+
+    fn path(&self, endpoint: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.json", endpoint.replace('/', "_")))
+    }
+
+    /// Whatever is cached for the endpoint, no matter how old
+    pub(super) async fn read(&self, endpoint: &str) -> Option<CachedResponse> {
+        let path = self.path(endpoint);
+        let modified = fs::metadata(&path).await.ok()?.modified().ok()?;
+        let body = fs::read_to_string(&path).await.ok()?;
+
+        Some(CachedResponse {
+            body,
+            age: SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or_default(),
+        })
+    }
+
+    pub(super) async fn write(&self, endpoint: &str, body: &str) -> TResult<()> {
+        fs::create_dir_all(&self.dir).await?;
+        fs::write(self.path(endpoint), body).await?;
+        Ok(())
+    }
+}
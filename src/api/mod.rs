@@ -0,0 +1,189 @@
+use std::time::Duration;
+
+use color_print::cprintln;
+use reqwest_middleware::ClientWithMiddleware;
+use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{
+    config::get_config,
+    errors::{Error, TResult},
+    http::client_builder,
+};
+
+use self::cache::ResponseCache;
+
+mod cache;
+pub mod models;
+
+const DEFAULT_BASE_URL: &str = "https://api.truckersmp.com/v2/";
+
+// how long a cached response is good for, per endpoint
+const SERVERS_TTL: Duration = Duration::from_secs(30);
+const GAME_TIME_TTL: Duration = Duration::from_secs(30);
+const VERSION_TTL: Duration = Duration::from_secs(10 * 60);
+const PLAYER_TTL: Duration = Duration::from_secs(10 * 60);
+const RULES_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The one client every TruckersMP API call goes through
+#[derive(Clone)]
+pub struct ApiClient {
+    client: ClientWithMiddleware,
+    base_url: String,
+    /// Best-effort, the API works just the same without it
+    cache: Option<ResponseCache>,
+    use_cache: bool,
+}
+
+impl ApiClient {
+    /// A client set up with the `[api]` section of the config
+    pub fn new() -> TResult<Self> {
+        Self::with_timeout(Duration::from_secs(get_config()?.api.timeout))
+    }
+
+    pub fn with_timeout(timeout: Duration) -> TResult<Self> {
+        let api = &get_config()?.api;
+
+        let client = client_builder()?.timeout(timeout).build()?;
+        let client = reqwest_middleware::ClientBuilder::new(client)
+            .with(RetryTransientMiddleware::new_with_policy(
+                ExponentialBackoff::builder().build_with_max_retries(api.retries),
+            ))
+            .build();
+
+        let mut base_url = api
+            .base_url
+            .clone()
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+
+        Ok(Self {
+            client,
+            base_url,
+            cache: ResponseCache::new(),
+            use_cache: true,
+        })
+    }
+
+    /// Always asks the API, without ever falling back to what's cached
+    pub fn no_cache(mut self, no_cache: bool) -> Self {
+        self.use_cache = !no_cache;
+        self
+    }
+
+    pub async fn version(&self) -> TResult<models::Version> {
+        self.get("version", VERSION_TTL).await
+    }
+
+    pub async fn servers(&self) -> TResult<Vec<models::Server>> {
+        self.get("servers", SERVERS_TTL).await
+    }
+
+    pub async fn game_time(&self) -> TResult<models::GameTime> {
+        self.get("game_time", GAME_TIME_TTL).await
+    }
+
+    pub async fn rules(&self) -> TResult<models::Rules> {
+        self.get("rules", RULES_TTL).await
+    }
+
+    pub async fn player(&self, id: u64) -> TResult<models::Player> {
+        self.get(&format!("player/{id}"), PLAYER_TTL).await
+    }
+
+    pub async fn bans(&self, id: u64) -> TResult<Vec<models::Ban>> {
+        self.get(&format!("bans/{id}"), PLAYER_TTL).await
+    }
+
+    async fn get<T: DeserializeOwned>(&self, endpoint: &str, ttl: Duration) -> TResult<T> {
+        let cache = self.cache.as_ref().filter(|_| self.use_cache);
+
+        if let Some(cache) = cache
+            && let Some(cached) = cache.read(endpoint).await
+            && cached.age < ttl
+        {
+            return decode(&cached.body);
+        }
+
+        let body = match self.fetch(endpoint).await {
+            Ok(body) => body,
+            Err(e @ (Error::ReqwestMiddlewareClientError(_) | Error::ReqwestError(_))) => {
+                // offline? stale data beats no data
+                let cached = match cache {
+                    Some(cache) => cache.read(endpoint).await,
+                    None => None,
+                };
+                let Some(cached) = cached else {
+                    return Err(e);
+                };
+                cprintln!(
+                    "<yellow,bold>warning</>: couldn't reach the TruckersMP API, using data from {} minute(s) ago",
+                    cached.age.as_secs() / 60
+                );
+                return decode(&cached.body);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let result = decode(&body)?;
+        // a read-only or full disk shouldn't get in the way of a perfectly good response
+        if let Some(cache) = &self.cache {
+            _ = cache.write(endpoint, &body).await;
+        }
+        Ok(result)
+    }
+
+    async fn fetch(&self, endpoint: &str) -> TResult<String> {
+        let resp = self
+            .client
+            .get(format!("{}{endpoint}", self.base_url))
+            .send()
+            .await?;
+        let status = resp.status();
+        let body = resp.text().await?;
+
+        // errors usually come with a descriptor in the body, so that goes first
+        if !status.is_success() {
+            return match decode::<Value>(&body) {
+                Err(Error::JsonError(_)) | Ok(_) => Err(Error::TruckersMPError(format!(
+                    "the API answered with {status}"
+                ))),
+                Err(e) => Err(e),
+            };
+        }
+
+        Ok(body)
+    }
+}
+
+/// Unwraps TruckersMP's `{ "error": .., "descriptor": .., "response": .. }` envelope.
+/// Some endpoints (like `/version`) don't use it at all and others put their data next to
+/// `error` instead of inside `response`, so both are handled.
+fn decode<T: DeserializeOwned>(body: &str) -> TResult<T> {
+    let mut value: Value = serde_json::from_str(body)?;
+
+    // making it a a string when the other things are bool is just a sin :(
+    let errored = match value.get("error") {
+        Some(Value::Bool(error)) => *error,
+        Some(Value::String(error)) => error == "true",
+        _ => false,
+    };
+
+    if errored {
+        let descriptor = value
+            .get("descriptor")
+            .and_then(Value::as_str)
+            .unwrap_or("it didn't say why");
+        return Err(Error::TruckersMPError(descriptor.to_string()));
+    }
+
+    let payload = match value.get_mut("response") {
+        Some(response) => response.take(),
+        None => value,
+    };
+
+    Ok(serde_json::from_value(payload)?)
+}
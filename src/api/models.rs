@@ -0,0 +1,126 @@
+//! Typed responses of the TruckersMP API. The `error`/`response` envelope is already
+//! stripped away by [`super::ApiClient`] by the time these get deserialized.
+
+use crate::game::Game;
+
+/// `/v2/version`
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Version {
+    #[serde(rename = "name")]
+    pub version: String,
+    pub numeric: String,
+    pub stage: String,
+    pub ets2mp_checksum: Checksum,
+    pub atsmp_checksum: Checksum,
+    pub time: String,
+    #[serde(rename = "supported_game_version")]
+    pub supported_ets2_version: String,
+    #[serde(rename = "supported_ats_game_version")]
+    pub supported_ats_version: String,
+}
+
+impl Version {
+    pub fn supported_version(&self, game: Game) -> &str {
+        match game {
+            Game::ETS2 => &self.supported_ets2_version,
+            Game::ATS => &self.supported_ats_version,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Checksum {
+    pub dll: String,
+    pub adb: String,
+}
+
+/// `/v2/servers`
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Server {
+    // id: u32,
+    pub game: Game,
+    pub ip: String,
+    // port: u16,
+    pub name: String,
+    #[serde(rename = "shortname")]
+    pub short_name: String,
+    // #[serde(rename = "idprefix")]
+    // id_prefix: Option<String>,
+    pub online: bool,
+    pub players: u32,
+    pub queue: u32,
+    #[serde(rename = "maxplayers")]
+    pub max_players: u32,
+    // #[serde(rename = "mapid")]
+    // map_id: u32,
+    // #[serde(rename = "displayorder")]
+    // display_order: u32,
+    #[serde(rename = "speedlimiter")]
+    pub speed_limiter: u32,
+    pub collisions: bool,
+    #[serde(rename = "carsforplayers")]
+    pub cars_for_players: bool,
+    #[serde(rename = "policecarsforplayers")]
+    pub police_cars_for_players: bool,
+    #[serde(rename = "afkenabled")]
+    pub afk_enabled: bool,
+    pub event: bool,
+    // #[serde(rename = "specialEvent")] // who made this api response...
+    // special_event: bool,
+    pub promods: bool,
+    // #[serde(rename = "syncdelay")]
+    // sync_delay: u32,
+}
+
+/// `/v2/game_time`
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct GameTime {
+    /// In-game minutes since the servers started counting
+    pub game_time: u64,
+}
+
+/// `/v2/rules`
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Rules {
+    /// The rules as markdown
+    pub rules: String,
+    pub revision: u32,
+}
+
+/// `/v2/player/{id}`
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Player {
+    pub id: u64,
+    pub name: String,
+    pub join_date: String,
+    #[serde(rename = "steamID64")]
+    pub steam_id64: u64,
+    pub group_name: String,
+    pub banned: bool,
+    pub banned_until: Option<String>,
+    pub bans_count: Option<u32>,
+    pub display_bans: bool,
+    pub vtc: PlayerVtc,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerVtc {
+    // id: u64,
+    pub name: String,
+    pub tag: String,
+    #[serde(rename = "inVTC")]
+    pub in_vtc: bool,
+}
+
+/// `/v2/bans/{id}`
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ban {
+    pub expiration: Option<String>,
+    pub time_added: String,
+    pub active: bool,
+    pub reason: String,
+    pub admin_name: String,
+}
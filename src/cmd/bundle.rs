@@ -0,0 +1,176 @@
+use std::{
+    io::Write as _,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use color_print::cprintln;
+use flate2::read::GzDecoder;
+use tokio::fs;
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+use crate::{
+    api::{ApiClient, models::Version},
+    cmd::{
+        update::{
+            ContentFile, check_file_hash, get_app_dir, get_cached_manifest_path,
+            parse_content_files,
+        },
+        verify::{FileStatus, verify_files},
+    },
+    errors::{Error, TResult},
+    game::Game,
+};
+
+/// Installs the mod files from a local directory or archive, checking them against the
+/// bundled `files.json` (or the cached one) instead of downloading anything.
+pub(super) async fn install_from(source: &Path, game: Game, content_dir: &Path) -> TResult<()> {
+    if source.is_dir() {
+        return install_from_dir(source, game, content_dir).await;
+    }
+
+    let extract_dir = get_app_dir()?.join("bundle");
+    if extract_dir.exists() {
+        fs::remove_dir_all(&extract_dir).await?;
+    }
+    fs::create_dir_all(&extract_dir).await?;
+
+    extract_bundle(source.to_path_buf(), extract_dir.clone()).await?;
+    let result = install_from_dir(&extract_dir, game, content_dir).await;
+
+    _ = fs::remove_dir_all(&extract_dir).await;
+    result
+}
+
+async fn install_from_dir(root: &Path, game: Game, content_dir: &Path) -> TResult<()> {
+    let manifest_path = root.join("files.json");
+    let manifest = if manifest_path.exists() {
+        fs::read_to_string(&manifest_path).await?
+    } else {
+        let cached_manifest = get_cached_manifest_path()?;
+        if !cached_manifest.exists() {
+            return Err(Error::NoContentManifest);
+        }
+
+        println!("The bundle has no files.json, using the cached one instead");
+        fs::read_to_string(cached_manifest).await?
+    };
+    let files = parse_content_files(&manifest)?.for_game(game);
+    check_bundle_version(root).await?;
+
+    let report = verify_files(&files, root).await?;
+    let failed = report
+        .iter()
+        .filter(|file| file.status != FileStatus::Ok)
+        .collect::<Vec<_>>();
+
+    if !failed.is_empty() {
+        for file in &failed {
+            println!("{}: {}", file.path, file.status.to_str());
+        }
+        return Err(Error::IncompleteBundle(failed.len()));
+    }
+
+    for file in &files {
+        let path = content_dir.join(&file.file_path);
+        if path.exists() && check_file_hash(file, &path).await? {
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::copy(root.join(&file.file_path), &path).await?;
+    }
+
+    println!("Finished installing files");
+    Ok(())
+}
+
+// an outdated bundle still installs fine, the game just won't like it once it's online
+async fn check_bundle_version(root: &Path) -> TResult<()> {
+    let version_path = root.join("version.json");
+    if !version_path.exists() {
+        return Ok(());
+    }
+
+    let bundled: Version = serde_json::from_str(&fs::read_to_string(version_path).await?)?;
+    let api_client = ApiClient::with_timeout(Duration::from_secs(5))?;
+
+    match api_client.version().await {
+        Ok(current) if current.version != bundled.version => cprintln!(
+            "<yellow,bold>warning</>: the bundle was made for mod version {} but the current one is {}",
+            bundled.version,
+            current.version
+        ),
+        Ok(current) => println!(
+            "The bundle is up to date with mod version {}",
+            current.version
+        ),
+        Err(_) => println!(
+            "Couldn't reach the TruckersMP API to check it, but the bundle was made for mod version {}",
+            bundled.version
+        ),
+    }
+
+    Ok(())
+}
+
+/// Writes the given mod files along with their `files.json` and `/v2/version` info into a zip.
+/// The layout matches what [`install_from`] expects.
+pub(super) async fn write_bundle(
+    bundle: &Path,
+    content_dir: &Path,
+    files: &[ContentFile],
+    manifest: String,
+    version: String,
+) -> TResult<()> {
+    let bundle = bundle.to_path_buf();
+    let content_dir = content_dir.to_path_buf();
+    let files = files.to_vec();
+
+    tokio::task::spawn_blocking(move || {
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut writer = ZipWriter::new(std::fs::File::create(&bundle)?);
+
+        writer.start_file("files.json", options)?;
+        writer.write_all(manifest.as_bytes())?;
+        writer.start_file("version.json", options)?;
+        writer.write_all(version.as_bytes())?;
+
+        for file in files {
+            writer.start_file(&file.file_path, options)?;
+            std::io::copy(
+                &mut std::fs::File::open(content_dir.join(&file.file_path))?,
+                &mut writer,
+            )?;
+        }
+
+        writer.finish()?;
+        Ok::<(), Error>(())
+    })
+    .await?
+}
+
+// both zip and tar are blocking so off they go to their own thread
+async fn extract_bundle(archive: PathBuf, to: PathBuf) -> TResult<()> {
+    tokio::task::spawn_blocking(move || {
+        let name = archive
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if name.ends_with(".zip") {
+            zip::ZipArchive::new(std::fs::File::open(&archive)?)?.extract(&to)?;
+        } else if name.ends_with(".tar") {
+            tar::Archive::new(std::fs::File::open(&archive)?).unpack(&to)?;
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            tar::Archive::new(GzDecoder::new(std::fs::File::open(&archive)?)).unpack(&to)?;
+        } else {
+            return Err(Error::UnsupportedBundle(archive));
+        }
+
+        Ok(())
+    })
+    .await?
+}
@@ -0,0 +1,86 @@
+use std::{cmp::Ordering, path::Path};
+
+use color_print::cprintln;
+
+use crate::{
+    api::ApiClient,
+    errors::{Error, TResult},
+    game::{Game, GameVersion, get_game_version},
+    steam::manifest::read_app_manifest,
+};
+
+// TruckersMP only ever supports the default branch or the matching temporary one
+const PUBLIC_BETA: &str = "public_beta";
+const TEMPORARY_BRANCH_PREFIX: &str = "temporary_";
+
+/// Makes sure the installed game is a release the current mod supports, since launching
+/// anything else just ends in a crash or a "version mismatch" popup once the game is up.
+pub(super) async fn check_game_version(game: Game, game_path: &Path, force: bool) -> TResult<()> {
+    let manifest = read_app_manifest(game, game_path).await.ok();
+    if let Some(manifest) = &manifest
+        && manifest.beta_key.as_deref() == Some(PUBLIC_BETA)
+    {
+        let build = manifest
+            .build_id
+            .map(|id| format!(" (build {id})"))
+            .unwrap_or_default();
+        cprintln!(
+            "<yellow,bold>warning</>: {:?} is on the {PUBLIC_BETA} branch{build}, which TruckersMP never supports. Switch back to the default branch in Steam (Properties > Betas)",
+            game
+        );
+    }
+
+    let installed = match get_game_version(game_path) {
+        Ok(Some(installed)) => installed,
+        // an exe we can't make sense of shouldn't stop the launch, same as everything else here
+        result => {
+            let reason = result.err().map(|e| format!(": {e}")).unwrap_or_default();
+            cprintln!(
+                "<yellow,bold>warning</>: couldn't read the version of {:?}, skipping the version check{reason}",
+                game
+            );
+            return Ok(());
+        }
+    };
+
+    let supported = match ApiClient::new()?.version().await {
+        Ok(version) => GameVersion::parse(version.supported_version(game)),
+        Err(e) => {
+            cprintln!(
+                "<yellow,bold>warning</>: couldn't get the supported game version, skipping the version check: {e}"
+            );
+            return Ok(());
+        }
+    };
+    let Some(supported) = supported else {
+        return Ok(());
+    };
+
+    if installed.same_release(&supported) {
+        return Ok(());
+    }
+
+    // someone left on an old temporary branch won't get anywhere with "update the game"
+    // as Steam keeps them on that release until they switch branches
+    let stale_branch = manifest
+        .and_then(|manifest| manifest.beta_key)
+        .filter(|key| key.starts_with(TEMPORARY_BRANCH_PREFIX) && *key != supported.beta_branch());
+
+    let error = match (stale_branch, installed.cmp(&supported)) {
+        (Some(branch), _) => {
+            let beta_branch = supported.beta_branch();
+            Error::GameOnStaleBranch(game, installed, branch, supported, beta_branch)
+        }
+        (None, Ordering::Greater) => {
+            Error::GameTooNew(game, installed, supported, supported.beta_branch())
+        }
+        (None, _) => Error::GameTooOld(game, installed, supported),
+    };
+
+    if force {
+        cprintln!("<yellow,bold>warning</>: {error}");
+        return Ok(());
+    }
+
+    Err(error)
+}
@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+
+use tokio::fs;
+
+use crate::{
+    api::ApiClient,
+    cmd::{
+        ContentExport, Run,
+        bundle::write_bundle,
+        update::{
+            build_reqwest_client, get_cached_manifest_path, get_content_dir, get_content_files,
+        },
+        verify::{FileStatus, verify_files},
+    },
+    errors::{Error, TResult},
+    game::Game,
+};
+
+impl Run for ContentExport {
+    async fn run(&self) -> TResult<()> {
+        let reqwest_client = build_reqwest_client(3)?;
+        let content_files = get_content_files(&reqwest_client).await?;
+        let content_dir = get_content_dir()?;
+
+        let games = match self.game {
+            Some(game) => vec![game],
+            None => vec![Game::ETS2, Game::ATS],
+        };
+
+        let mut files = Vec::new();
+        let mut exported_games = Vec::new();
+        for game in games {
+            let game_files = content_files.for_game(game);
+            let failed = verify_files(&game_files, &content_dir)
+                .await?
+                .iter()
+                .filter(|file| file.status != FileStatus::Ok)
+                .count();
+
+            if failed == 0 {
+                exported_games.push(game);
+                files.extend(game_files);
+            } else if self.game.is_some() {
+                return Err(Error::VerificationFailed(failed));
+            } else {
+                println!("Skipping {game:?} as {failed} of its mod file(s) failed verification");
+            }
+        }
+
+        if exported_games.is_empty() {
+            return Err(Error::NothingToExport);
+        }
+
+        // the shared files are listed once per game
+        let mut seen = HashSet::new();
+        files.retain(|file| seen.insert(file.file_path.clone()));
+
+        let game_info = ApiClient::new()?.version().await?;
+
+        let manifest = fs::read_to_string(get_cached_manifest_path()?).await?;
+        let version = serde_json::to_string_pretty(&game_info)?;
+
+        println!(
+            "Exporting {} mod files for {:?} (mod version {})",
+            files.len(),
+            exported_games,
+            game_info.version
+        );
+        write_bundle(&self.file, &content_dir, &files, manifest, version).await?;
+        println!("Bundle written to {}", self.file.display());
+
+        Ok(())
+    }
+}
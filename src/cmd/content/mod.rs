@@ -0,0 +1,25 @@
+use clap::CommandFactory as _;
+
+use crate::{
+    cmd::{ContentCmd, ContentCommand, Run},
+    errors::TResult,
+};
+
+pub mod export;
+pub mod serve;
+
+impl Run for ContentCmd {
+    async fn run(&self) -> TResult<()> {
+        match &self.cmd {
+            Some(cmd) => match cmd {
+                ContentCommand::Export(cmd) => cmd.run().await?,
+                ContentCommand::Serve(cmd) => cmd.run().await?,
+            },
+            None => {
+                let mut cmd = ContentCmd::command();
+                cmd.print_help()?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
+
+use axum::{
+    Router,
+    extract::{Path, Request, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use color_print::cprintln;
+use tokio::{fs, net::TcpListener};
+use tower::ServiceExt as _;
+use tower_http::services::ServeFile;
+
+use crate::{
+    cmd::{
+        ContentServe, Run,
+        update::{
+            build_reqwest_client, filter_manifest, get_cached_manifest_path, get_content_dir,
+            get_content_files, parse_content_files,
+        },
+        verify::{FileStatus, verify_files},
+    },
+    errors::{Error, TResult},
+};
+
+struct MirrorState {
+    manifest: String,
+    content_dir: PathBuf,
+    verified_files: HashSet<String>,
+}
+
+impl Run for ContentServe {
+    async fn run(&self) -> TResult<()> {
+        let reqwest_client = build_reqwest_client(3)?;
+        if let Err(e) = get_content_files(&reqwest_client).await {
+            cprintln!(
+                "<yellow,bold>warning</>: couldn't fetch the latest files.json, serving the cached one: {e}"
+            );
+        }
+
+        let manifest = fs::read_to_string(get_cached_manifest_path()?)
+            .await
+            .map_err(|_| Error::NoContentManifest)?;
+        let content_files = parse_content_files(&manifest)?.all();
+        let content_dir = get_content_dir()?;
+
+        let report = verify_files(&content_files, &content_dir).await?;
+        let verified_files = report
+            .into_iter()
+            .filter(|file| file.status == FileStatus::Ok)
+            .map(|file| file.path)
+            .collect::<HashSet<_>>();
+
+        println!(
+            "{} of {} mod files passed verification and will be served",
+            verified_files.len(),
+            content_files.len()
+        );
+
+        // clients would only keep asking for the files we refuse to serve otherwise
+        let manifest = filter_manifest(&manifest, |path| verified_files.contains(path))?;
+
+        let state = Arc::new(MirrorState {
+            manifest,
+            content_dir,
+            verified_files,
+        });
+
+        // same layout as UPDATE_URL and DOWNLOAD_URL so only the host has to change
+        let app = Router::new()
+            .route("/files.json", get(serve_manifest))
+            .route("/files/{*path}", get(serve_file))
+            .with_state(state);
+
+        let listener = TcpListener::bind(self.bind).await?;
+        println!("Serving the mod files on http://{}", self.bind);
+        axum::serve(listener, app).await?;
+
+        Ok(())
+    }
+}
+
+async fn serve_manifest(State(state): State<Arc<MirrorState>>) -> Response {
+    (
+        [(header::CONTENT_TYPE, "application/json")],
+        state.manifest.clone(),
+    )
+        .into_response()
+}
+
+async fn serve_file(
+    State(state): State<Arc<MirrorState>>,
+    Path(path): Path<String>,
+    request: Request,
+) -> Response {
+    // only what's in the allow list ever gets served, so no funny `..` business either
+    if !state.verified_files.contains(&path) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    // ServeFile takes care of the Range requests for us
+    match ServeFile::new(state.content_dir.join(&path))
+        .oneshot(request)
+        .await
+    {
+        Ok(response) => response.into_response(),
+        Err(infallible) => match infallible {},
+    }
+}
@@ -5,13 +5,19 @@ use crate::{
     errors::TResult,
 };
 
+pub mod player;
+pub mod rules;
 pub mod server;
+pub mod time;
 
 impl Run for GameCmd {
     async fn run(&self) -> TResult<()> {
         match &self.cmd {
             Some(cmd) => match cmd {
                 GameCommand::Servers(cmd) => cmd.run().await?,
+                GameCommand::Time(cmd) => cmd.run().await?,
+                GameCommand::Rules(cmd) => cmd.run().await?,
+                GameCommand::Player(cmd) => cmd.run().await?,
             },
             None => {
                 let mut cmd = GameCmd::command();
@@ -0,0 +1,60 @@
+use color_print::cprintln;
+use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+
+use crate::{
+    api::ApiClient,
+    cmd::{PlayerInfo, Run},
+    errors::TResult,
+};
+
+impl Run for PlayerInfo {
+    async fn run(&self) -> TResult<()> {
+        let api_client = ApiClient::new()?.no_cache(self.no_cache);
+        let player = api_client.player(self.id).await?;
+
+        cprintln!("<bold>{}</> ({})", player.name, player.id);
+        println!("Group: {}", player.group_name);
+        println!("Joined: {}", player.join_date);
+        println!("Steam ID: {}", player.steam_id64);
+        if player.vtc.in_vtc {
+            println!("VTC: {} [{}]", player.vtc.name, player.vtc.tag);
+        }
+
+        if player.banned {
+            cprintln!(
+                "<red,bold>Banned</> until {}",
+                player.banned_until.as_deref().unwrap_or("forever")
+            );
+        }
+
+        if !player.display_bans {
+            println!("Bans: hidden by the player");
+            return Ok(());
+        }
+
+        println!("Bans: {}", player.bans_count.unwrap_or(0));
+        let bans = api_client.bans(self.id).await?;
+        if bans.is_empty() {
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_header(vec!["Added", "Expires", "Reason", "Admin"]);
+
+        for ban in bans {
+            table.add_row(vec![
+                Cell::new(ban.time_added).fg(if ban.active { Color::Red } else { Color::Reset }),
+                Cell::new(ban.expiration.unwrap_or_else(|| "Never".to_string())),
+                Cell::new(ban.reason),
+                Cell::new(ban.admin_name),
+            ]);
+        }
+
+        println!("{table}");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+use crate::{
+    api::ApiClient,
+    cmd::{RulesInfo, Run},
+    errors::TResult,
+};
+
+impl Run for RulesInfo {
+    async fn run(&self) -> TResult<()> {
+        let rules = ApiClient::new()?.no_cache(self.no_cache).rules().await?;
+
+        println!("{}", rules.rules);
+        println!("Revision: {}", rules.revision);
+
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@ use std::{
     time::Duration,
 };
 
-use clap::{ValueEnum, crate_version};
+use clap::ValueEnum;
 use color_print::cformat;
 use comfy_table::{
     Attribute, Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL,
@@ -15,17 +15,21 @@ use rand::random;
 use surge_ping::{Client as PingClient, Config as PingConfig, PingIdentifier, PingSequence};
 
 use crate::{
+    api::{ApiClient, models},
     cmd::{Run, ServerInfo},
-    errors::{Error, TResult},
+    errors::TResult,
     game::Game,
 };
 
 impl Run for ServerInfo {
     async fn run(&self) -> TResult<()> {
-        let reqwest_client = reqwest::Client::builder()
-            .user_agent(format!("Yet Another TruckersMP Cli/{:?}", crate_version!()))
-            .build()?;
-        let servers: Vec<Server> = get_servers(&reqwest_client).await?.into();
+        let servers = ApiClient::new()?
+            .no_cache(self.no_cache)
+            .servers()
+            .await?
+            .into_iter()
+            .map(Server::from)
+            .collect::<Vec<_>>();
 
         let servers = if let Some(game) = self.game {
             servers
@@ -106,20 +110,16 @@ impl Run for ServerInfo {
 
             let mut row = vec![
                 Cell::new(server.name)
-                    .add_attribute((|| {
-                        if server.online {
-                            Attribute::NormalIntensity
-                        } else {
-                            Attribute::Dim
-                        }
-                    })())
-                    .fg((|| {
-                        if server.online {
-                            Color::Green
-                        } else {
-                            Color::Red
-                        }
-                    })()),
+                    .add_attribute(if server.online {
+                        Attribute::NormalIntensity
+                    } else {
+                        Attribute::Dim
+                    })
+                    .fg(if server.online {
+                        Color::Green
+                    } else {
+                        Color::Red
+                    }),
                 Cell::new(format!("{:?}", server.game)),
                 Cell::new(types),
                 Cell::new(format!(
@@ -230,8 +230,8 @@ struct Server {
     ip: IpAddr,
 }
 
-impl From<RawServer> for Server {
-    fn from(value: RawServer) -> Self {
+impl From<models::Server> for Server {
+    fn from(value: models::Server) -> Self {
         let mut server_type = Vec::new();
 
         if value.promods {
@@ -282,86 +282,6 @@ impl From<RawServer> for Server {
     }
 }
 
-impl From<RawServerInfo> for Vec<Server> {
-    fn from(value: RawServerInfo) -> Self {
-        value.response.into_iter().map(Server::from).collect()
-    }
-}
-
-#[derive(Debug, serde::Deserialize)]
-struct RawServerInfo {
-    #[serde(deserialize_with = "deserialize_bool")]
-    error: bool, // making it a a string when the other things are bool is just a sin :(
-    response: Vec<RawServer>,
-}
-
-fn deserialize_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
-where
-    D: serde::de::Deserializer<'de>,
-{
-    let s: &str = serde::de::Deserialize::deserialize(deserializer)?;
-
-    match s {
-        "true" => Ok(true),
-        "false" => Ok(false),
-        _ => Err(serde::de::Error::unknown_variant(s, &["true", "false"])),
-    }
-}
-
-#[derive(Debug, serde::Deserialize)]
-struct RawServer {
-    // id: u32,
-    game: Game,
-    ip: String,
-    // port: u16,
-    name: String,
-    #[serde(rename = "shortname")]
-    short_name: String,
-    // #[serde(rename = "idprefix")]
-    // id_prefix: Option<String>,
-    online: bool,
-    players: u32,
-    queue: u32,
-    #[serde(rename = "maxplayers")]
-    max_players: u32,
-    // #[serde(rename = "mapid")]
-    // map_id: u32,
-    // #[serde(rename = "displayorder")]
-    // display_order: u32,
-    #[serde(rename = "speedlimiter")]
-    speed_limiter: u32,
-    collisions: bool,
-    #[serde(rename = "carsforplayers")]
-    cars_for_players: bool,
-    #[serde(rename = "policecarsforplayers")]
-    police_cars_for_players: bool,
-    #[serde(rename = "afkenabled")]
-    afk_enabled: bool,
-    event: bool,
-    // #[serde(rename = "specialEvent")] // who made this api response...
-    // special_event: bool,
-    promods: bool,
-    // #[serde(rename = "syncdelay")]
-    // sync_delay: u32,
-}
-
-async fn get_servers(client: &reqwest::Client) -> TResult<RawServerInfo> {
-    let result: RawServerInfo = client
-        .get("https://api.truckersmp.com/v2/servers")
-        .send()
-        .await?
-        .json()
-        .await?;
-
-    if result.error {
-        return Err(Error::TruckersMPError);
-    }
-
-    // println!("raww: {:#?}", result);
-
-    Ok(result)
-}
-
 // single ping pong action
 async fn ping(client: PingClient, addr: IpAddr) -> (IpAddr, u32) {
     let payload = [0; 56];
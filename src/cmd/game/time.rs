@@ -0,0 +1,21 @@
+use crate::{
+    api::ApiClient,
+    cmd::{Run, TimeInfo},
+    errors::TResult,
+};
+
+impl Run for TimeInfo {
+    async fn run(&self) -> TResult<()> {
+        let game_time = ApiClient::new()?
+            .no_cache(self.no_cache)
+            .game_time()
+            .await?
+            .game_time;
+
+        // the api counts in-game minutes, so a day is 1440 of them
+        let minutes = game_time % 1440;
+        println!("In-game time: {:02}:{:02}", minutes / 60, minutes % 60);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use color_print::cprintln;
+use tokio::process::Command;
+
+use crate::{
+    config::{HooksConfig, get_config},
+    errors::{Error, TResult},
+    game::Game,
+};
+
+/// The points where a command from the `[hooks]` section of the config can be run
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Hook {
+    PreUpdate,
+    PostUpdate,
+    PreLaunch,
+    PostLaunch,
+    PostExit,
+}
+
+impl Hook {
+    fn name(self) -> &'static str {
+        match self {
+            Hook::PreUpdate => "pre_update",
+            Hook::PostUpdate => "post_update",
+            Hook::PreLaunch => "pre_launch",
+            Hook::PostLaunch => "post_launch",
+            Hook::PostExit => "post_exit",
+        }
+    }
+
+    fn command(self, hooks: &HooksConfig) -> Option<&String> {
+        match self {
+            Hook::PreUpdate => hooks.pre_update.as_ref(),
+            Hook::PostUpdate => hooks.post_update.as_ref(),
+            Hook::PreLaunch => hooks.pre_launch.as_ref(),
+            Hook::PostLaunch => hooks.post_launch.as_ref(),
+            Hook::PostExit => hooks.post_exit.as_ref(),
+        }
+    }
+
+    // a failing pre hook stops whatever was about to happen, the rest only get a warning
+    fn can_veto(self) -> bool {
+        matches!(self, Hook::PreUpdate | Hook::PreLaunch)
+    }
+}
+
+/// What a hook gets to know about, handed over as `TRUCKERSMP_*` env vars
+pub(super) struct HookContext<'a> {
+    pub(super) game: Game,
+    pub(super) game_path: Option<&'a Path>,
+    pub(super) content_dir: &'a Path,
+    pub(super) pid: Option<u32>,
+    pub(super) exit_code: Option<u32>,
+}
+
+pub(super) fn has_hook(hook: Hook) -> TResult<bool> {
+    Ok(hook.command(&get_config()?.hooks).is_some())
+}
+
+pub(super) async fn run_hook(hook: Hook, context: &HookContext<'_>) -> TResult<()> {
+    let Some(command) = hook.command(&get_config()?.hooks) else {
+        return Ok(());
+    };
+
+    println!("Running the {} hook", hook.name());
+
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C").arg(command);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(command);
+        shell
+    };
+
+    shell
+        .env("TRUCKERSMP_HOOK", hook.name())
+        .env("TRUCKERSMP_GAME", format!("{:?}", context.game))
+        .env("TRUCKERSMP_APP_ID", (context.game as u32).to_string())
+        .env("TRUCKERSMP_CONTENT_DIR", context.content_dir);
+    if let Some(game_path) = context.game_path {
+        shell.env("TRUCKERSMP_GAME_PATH", game_path);
+    }
+    if let Some(pid) = context.pid {
+        shell.env("TRUCKERSMP_PID", pid.to_string());
+    }
+    if let Some(exit_code) = context.exit_code {
+        shell.env("TRUCKERSMP_EXIT_CODE", exit_code.to_string());
+    }
+
+    let status = shell.status().await?;
+    if status.success() {
+        return Ok(());
+    }
+
+    if hook.can_veto() {
+        return Err(Error::HookFailed(hook.name(), status.code()));
+    }
+
+    cprintln!(
+        "<yellow,bold>warning</>: the {} hook failed with {}",
+        hook.name(),
+        status
+    );
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use crate::{
+    cmd::{InjectGame, Run},
+    errors::TResult,
+};
+
+impl Run for InjectGame {
+    #[cfg(windows)]
+    async fn run(&self) -> TResult<()> {
+        use std::collections::HashMap;
+
+        use crate::cmd::launch::{LaunchOptions, get_launcher};
+
+        let launched = get_launcher()?.launch(&LaunchOptions {
+            game: self.game,
+            game_path: &self.exe,
+            args: &self.args,
+            dlls: &self.dlls,
+            env: &HashMap::new(),
+        })?;
+
+        if !self.wait {
+            launched.detach();
+            return Ok(());
+        }
+
+        // whoever started us (Proton, usually) wants the game's exit code, not ours
+        let exit_code = launched.wait()?;
+        if let Some(exit_code_file) = &self.exit_code_file {
+            std::fs::write(exit_code_file, exit_code.to_string())?;
+        }
+        std::process::exit(exit_code as i32);
+    }
+
+    #[cfg(not(windows))]
+    async fn run(&self) -> TResult<()> {
+        Err(crate::errors::Error::InjectNotSupported)
+    }
+}
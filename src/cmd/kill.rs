@@ -1,42 +1,107 @@
-use sysinfo::{ProcessRefreshKind, RefreshKind, System, UpdateKind};
+use std::path::Path;
+
+use sysinfo::{Pid, Process, ProcessRefreshKind, RefreshKind, System, UpdateKind};
 
 use crate::{
     cmd::{Kill, Run},
     errors::TResult,
-    game::{get_available_games, get_game_path, get_specific_game, get_steamworks_client},
+    game::{Game, get_game_path, pick_game},
 };
 
+/// Takes a snapshot of the running processes, with just enough info to find the game in it
+pub(super) fn scan_processes() -> System {
+    System::new_with_specifics(
+        RefreshKind::nothing()
+            .with_processes(ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet)),
+    )
+}
+
+pub(super) fn find_game_process<'a>(
+    sysinfo: &'a System,
+    game: Game,
+    game_path: &Path,
+) -> Option<&'a Process> {
+    sysinfo
+        .processes()
+        .values()
+        .find(|process| process.exe() == Some(game_path))
+        // under wine the exe is wine's own, but the name is still the game's
+        .or_else(|| {
+            sysinfo
+                .processes()
+                .values()
+                .find(|process| process.name().eq_ignore_ascii_case(game.exe()))
+        })
+}
+
+/// Whether the game process has the TruckersMP dll loaded, `None` when there's no telling
+#[cfg(windows)]
+pub(super) fn has_truckersmp_loaded(game: Game, pid: Pid) -> Option<bool> {
+    is_module_loaded(pid.as_u32(), game.dll())
+}
+
+/// Looks through the process' modules for a dll with the given file name
+#[cfg(windows)]
+pub(super) fn is_module_loaded(pid: u32, module: &str) -> Option<bool> {
+    use windows::Win32::{
+        Foundation::CloseHandle,
+        System::Diagnostics::ToolHelp::{
+            CreateToolhelp32Snapshot, MODULEENTRY32W, Module32FirstW, Module32NextW,
+            TH32CS_SNAPMODULE,
+        },
+    };
+
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPMODULE, pid).ok()?;
+
+        let mut entry = MODULEENTRY32W {
+            dwSize: std::mem::size_of::<MODULEENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut loaded = false;
+        let mut next = Module32FirstW(snapshot, &mut entry);
+        while next.is_ok() {
+            let len = entry.szModule.iter().position(|&c| c == 0).unwrap_or(256);
+            if String::from_utf16_lossy(&entry.szModule[..len]).eq_ignore_ascii_case(module) {
+                loaded = true;
+                break;
+            }
+            next = Module32NextW(snapshot, &mut entry);
+        }
+        CloseHandle(snapshot).ok();
+
+        Some(loaded)
+    }
+}
+
+// wine maps the dlls like any other file, so they show up in the process' memory maps
+#[cfg(target_os = "linux")]
+pub(super) fn has_truckersmp_loaded(game: Game, pid: Pid) -> Option<bool> {
+    let maps = std::fs::read_to_string(format!("/proc/{pid}/maps")).ok()?;
+    let dll = game.dll().to_lowercase();
+
+    Some(maps.lines().any(|line| line.to_lowercase().ends_with(&dll)))
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub(super) fn has_truckersmp_loaded(_game: Game, _pid: Pid) -> Option<bool> {
+    None
+}
+
 impl Run for Kill {
     async fn run(&self) -> TResult<()> {
-        let steamworks = get_steamworks_client()?;
-
-        let game = if let Some(game) = self.game {
-            get_specific_game(&steamworks, game)
-        } else {
-            get_available_games(&steamworks)
-        }?;
-
-        let sysinfo = System::new_with_specifics(
-            RefreshKind::nothing()
-                .with_processes(ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet)),
-        );
-        // sysinfo.refresh_processes_specifics(
-        //     ProcessesToUpdate::All,
-        //     true,
-        //     ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet),
-        // );
-
-        let game_path = get_game_path(&steamworks, game)?;
+        let (game, source) = pick_game(self.game, self.game_path.as_deref())?;
+
+        let sysinfo = scan_processes();
+
+        let game_path = get_game_path(&*source, game)?;
         println!("game_path: {:?}", game_path);
 
-        for (_, process) in sysinfo.processes() {
-            if process.exe() == Some(&game_path) {
-                println!("Found game process! Killing it... :3");
-                match process.kill() {
-                    true => println!("Game process killed successfully"),
-                    false => println!("Brutally failed to kill the game process"),
-                }
-                break;
+        if let Some(process) = find_game_process(&sysinfo, game, &game_path) {
+            println!("Found game process! Killing it... :3");
+            match process.kill() {
+                true => println!("Game process killed successfully"),
+                false => println!("Brutally failed to kill the game process"),
             }
         }
 
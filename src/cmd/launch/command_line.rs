@@ -0,0 +1,97 @@
+//! Windows command lines are a single string that the program splits back up itself, following
+//! `CommandLineToArgvW`'s rules. Nothing Windows specific about building one, so it's tested
+//! everywhere.
+
+use std::path::Path;
+
+/// Builds a command line that `CommandLineToArgvW` splits back into the exact same arguments
+pub(super) fn build_command_line(exe: &Path, args: &[String]) -> String {
+    let mut command_line = format!("\"{}\"", exe.display());
+
+    for arg in args {
+        command_line.push(' ');
+        command_line.push_str(&quote_arg(arg));
+    }
+
+    command_line
+}
+
+// backslashes are only special when they end up right before a quote. windows moment
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '\n', '\x0b', '"']) {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.extend(std::iter::repeat_n('\\', backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.extend(std::iter::repeat_n('\\', backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    quoted.extend(std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_plain_args_alone() {
+        assert_eq!(quote_arg("-nointro"), "-nointro");
+        assert_eq!(quote_arg("C:\\games\\ets2"), "C:\\games\\ets2");
+    }
+
+    #[test]
+    fn quotes_empty_args_and_whitespace() {
+        assert_eq!(quote_arg(""), "\"\"");
+        assert_eq!(quote_arg("C:\\ets2 profiles"), "\"C:\\ets2 profiles\"");
+        assert_eq!(quote_arg("a\tb"), "\"a\tb\"");
+    }
+
+    #[test]
+    fn escapes_embedded_quotes() {
+        assert_eq!(quote_arg("say \"hi\""), "\"say \\\"hi\\\"\"");
+        // the backslash before the quote has to be doubled, plus one for the quote itself
+        assert_eq!(quote_arg("a\\\"b"), "\"a\\\\\\\"b\"");
+    }
+
+    #[test]
+    fn doubles_trailing_backslashes() {
+        // otherwise the closing quote would get escaped
+        assert_eq!(
+            quote_arg("C:\\ets2 profiles\\"),
+            "\"C:\\ets2 profiles\\\\\""
+        );
+        assert_eq!(quote_arg("a b\\\\"), "\"a b\\\\\\\\\"");
+    }
+
+    #[test]
+    fn builds_command_line() {
+        let args = [
+            "-homedir".to_string(),
+            "C:\\ets2 profiles\\".to_string(),
+            String::new(),
+        ];
+        assert_eq!(
+            build_command_line(
+                Path::new("C:\\Euro Truck Simulator 2\\bin\\win_x64\\eurotrucks2.exe"),
+                &args
+            ),
+            "\"C:\\Euro Truck Simulator 2\\bin\\win_x64\\eurotrucks2.exe\" -homedir \"C:\\ets2 profiles\\\\\" \"\""
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! Starting the game with the mod injected into it, which works quite differently on each OS.
+//! Everything OS specific lives behind [`Launcher`] so the rest of the CLI builds anywhere.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{errors::TResult, game::Game};
+
+#[cfg(any(windows, test))]
+mod command_line;
+#[cfg(target_os = "linux")]
+mod proton;
+#[cfg(windows)]
+mod windows;
+
+pub(super) struct LaunchOptions<'a> {
+    pub game: Game,
+    pub game_path: &'a Path,
+    pub args: &'a [String],
+    /// Injected in order while the game is still suspended
+    pub dlls: &'a [PathBuf],
+    /// Extra environment variables for the game
+    pub env: &'a HashMap<String, String>,
+}
+
+pub(super) trait Launcher {
+    fn launch(&self, options: &LaunchOptions) -> TResult<Box<dyn LaunchedGame>>;
+}
+
+/// A game we started
+pub(super) trait LaunchedGame {
+    fn pid(&self) -> u32;
+    /// Blocks until the game exits and returns its exit code
+    fn wait(self: Box<Self>) -> TResult<u32>;
+    /// Lets the game be, we just don't care about it anymore
+    fn detach(self: Box<Self>);
+}
+
+#[cfg(windows)]
+pub(super) fn get_launcher() -> TResult<Box<dyn Launcher>> {
+    Ok(Box::new(windows::Win32Launcher))
+}
+
+#[cfg(target_os = "linux")]
+pub(super) fn get_launcher() -> TResult<Box<dyn Launcher>> {
+    Ok(Box::new(proton::ProtonLauncher))
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub(super) fn get_launcher() -> TResult<Box<dyn Launcher>> {
+    Err(crate::errors::Error::UnsupportedPlatform)
+}
@@ -0,0 +1,396 @@
+//! Launching through Proton. There's no injecting into a Wine process from the outside, so the
+//! Windows build of the CLI is run inside the game's prefix and does the usual suspended launch
+//! and injection from there, through the hidden `inject` command.
+
+use std::{
+    path::{Path, PathBuf},
+    process::{Child, Command},
+    time::{Duration, Instant},
+};
+
+use color_print::cprintln;
+
+use super::{LaunchOptions, LaunchedGame, Launcher};
+use crate::{
+    cmd::{
+        kill::{find_game_process, scan_processes},
+        update::get_app_dir,
+    },
+    config::get_config,
+    errors::{Error, TResult},
+    game::Game,
+    steam::{library::find_steam_dir, manifest::get_steamapps_dir},
+};
+
+// Proton takes its time setting the prefix up before the game shows up
+const GAME_PROCESS_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub struct ProtonLauncher;
+
+struct ProtonGame {
+    child: Child,
+    /// The game itself, as opposed to the `proton` script we started
+    game_pid: Option<u32>,
+    exit_code_file: PathBuf,
+}
+
+impl LaunchedGame for ProtonGame {
+    fn pid(&self) -> u32 {
+        self.game_pid.unwrap_or_else(|| self.child.id())
+    }
+
+    fn wait(mut self: Box<Self>) -> TResult<u32> {
+        let status = self.child.wait()?;
+
+        // the windows side exits with the game's exit code too, but only the low 8 bits of it
+        // survive, which turns 0xC0000005 into 5
+        let exit_code = std::fs::read_to_string(&self.exit_code_file)
+            .ok()
+            .and_then(|code| code.trim().parse().ok());
+        _ = std::fs::remove_file(&self.exit_code_file);
+
+        exit_code
+            .or(status.code().map(|code| code as u32))
+            .ok_or(Error::UnknownGameExitCode)
+    }
+
+    // dropping the child doesn't kill it, Proton keeps going without us
+    fn detach(self: Box<Self>) {}
+}
+
+/// The game's Wine prefix, `<library>/steamapps/compatdata/<appid>`
+#[derive(Debug)]
+struct ProtonPrefix {
+    compat_data: PathBuf,
+    version: Option<String>,
+    /// Where the Proton this prefix was made with lives
+    proton_dir: Option<PathBuf>,
+}
+
+impl ProtonPrefix {
+    // Steam makes the prefix in the same library as the game
+    fn find(game: Game, game_path: &Path) -> TResult<Self> {
+        let compat_data = get_steamapps_dir(game_path)
+            .ok_or(Error::NoProtonPrefix(game))?
+            .join("compatdata")
+            .join((game as u32).to_string());
+
+        if !compat_data.join("pfx").exists() {
+            return Err(Error::NoProtonPrefix(game));
+        }
+
+        let config_info =
+            std::fs::read_to_string(compat_data.join("config_info")).unwrap_or_default();
+        let (version, proton_dir) = parse_config_info(&config_info);
+        let version = version.or_else(|| {
+            std::fs::read_to_string(compat_data.join("version"))
+                .ok()
+                .map(|version| version.trim().to_string())
+        });
+
+        Ok(ProtonPrefix {
+            compat_data,
+            version,
+            proton_dir,
+        })
+    }
+}
+
+/// `config_info` starts with the Proton version, followed by paths into that Proton's
+/// `files` (or `dist` on older ones) folder
+fn parse_config_info(config_info: &str) -> (Option<String>, Option<PathBuf>) {
+    let mut lines = config_info.lines().map(str::trim);
+    let version = lines
+        .next()
+        .filter(|version| !version.is_empty())
+        .map(String::from);
+
+    let proton_dir = lines.find_map(|line| {
+        let end = line.find("/files/").or_else(|| line.find("/dist/"))?;
+        Some(PathBuf::from(&line[..end]))
+    });
+
+    (version, proton_dir)
+}
+
+/// Waits for the game to show up. Proton exiting before it does means the launch or the
+/// injection went wrong inside the prefix
+fn find_game_pid(child: &mut Child, options: &LaunchOptions) -> TResult<Option<u32>> {
+    let started = Instant::now();
+
+    while started.elapsed() < GAME_PROCESS_TIMEOUT {
+        if let Some(status) = child.try_wait()? {
+            return Err(Error::ProtonHelperExited(options.game, status));
+        }
+
+        let sysinfo = scan_processes();
+        if let Some(process) = find_game_process(&sysinfo, options.game, options.game_path) {
+            return Ok(Some(process.pid().as_u32()));
+        }
+
+        std::thread::sleep(Duration::from_millis(500));
+    }
+
+    Ok(None)
+}
+
+/// Wine maps the whole filesystem to `Z:`
+fn to_wine_path(path: &Path) -> String {
+    format!("Z:{}", path.display()).replace('/', "\\")
+}
+
+struct Proton {
+    dir: PathBuf,
+    compat_data: PathBuf,
+    steam_dir: PathBuf,
+    /// The Windows build of the CLI
+    cli: PathBuf,
+    /// Where the CLI inside the prefix leaves the game's full exit code
+    exit_code_file: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ProtonCommand {
+    program: PathBuf,
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+}
+
+impl Proton {
+    fn command(&self, options: &LaunchOptions) -> ProtonCommand {
+        let mut args = vec![
+            "waitforexitandrun".to_string(),
+            self.cli.display().to_string(),
+            "inject".to_string(),
+            "--game".to_string(),
+            format!("{:?}", options.game).to_lowercase(),
+            "--wait".to_string(),
+            "--exit-code-file".to_string(),
+            to_wine_path(&self.exit_code_file),
+        ];
+        for dll in options.dlls {
+            args.push("--dll".to_string());
+            args.push(to_wine_path(dll));
+        }
+        args.push(to_wine_path(options.game_path));
+
+        if !options.args.is_empty() {
+            args.push("--".to_string());
+            args.extend(options.args.iter().cloned());
+        }
+
+        let app_id = (options.game as u32).to_string();
+        let env = vec![
+            (
+                "STEAM_COMPAT_DATA_PATH".to_string(),
+                self.compat_data.display().to_string(),
+            ),
+            (
+                "STEAM_COMPAT_CLIENT_INSTALL_PATH".to_string(),
+                self.steam_dir.display().to_string(),
+            ),
+            ("SteamGameId".to_string(), app_id.clone()),
+            ("SteamAppId".to_string(), app_id),
+        ];
+
+        ProtonCommand {
+            program: self.dir.join("proton"),
+            args,
+            env,
+        }
+    }
+}
+
+impl Launcher for ProtonLauncher {
+    fn launch(&self, options: &LaunchOptions) -> TResult<Box<dyn LaunchedGame>> {
+        let config = &get_config()?.proton;
+        let prefix = ProtonPrefix::find(options.game, options.game_path)?;
+
+        let dir = config
+            .path
+            .clone()
+            .or(prefix.proton_dir)
+            .ok_or(Error::UnknownProton(options.game))?;
+        let cli = match &config.cli {
+            Some(cli) => cli.clone(),
+            None => get_app_dir()?.join("truckersmp-cli.exe"),
+        };
+        if !cli.exists() {
+            return Err(Error::NoWindowsCli(cli));
+        }
+        // the Windows build links against the Steam API, it won't even start without it
+        let steam_api = cli.with_file_name("steam_api64.dll");
+        if !steam_api.exists() {
+            return Err(Error::NoSteamApiDll(steam_api));
+        }
+
+        let proton = Proton {
+            dir,
+            compat_data: prefix.compat_data,
+            steam_dir: find_steam_dir().ok_or(Error::SteamNotFound)?,
+            cli,
+            exit_code_file: std::env::temp_dir()
+                .join(format!("truckersmp-cli-exit-code-{}", std::process::id())),
+        };
+        println!(
+            "Using Proton {} from {}",
+            prefix.version.as_deref().unwrap_or("(unknown version)"),
+            proton.dir.display()
+        );
+
+        let command = proton.command(options);
+        _ = std::fs::remove_file(&proton.exit_code_file);
+        let mut child = Command::new(&command.program)
+            .args(&command.args)
+            .envs(command.env)
+            .envs(options.env)
+            .spawn()
+            .map_err(|_| Error::FailedGameLaunch)?;
+
+        let game_pid = find_game_pid(&mut child, options)?;
+        if game_pid.is_none() {
+            cprintln!(
+                "<yellow,bold>warning</>: couldn't find the {:?} process in time, hooks get Proton's pid instead",
+                options.game
+            );
+        }
+
+        Ok(Box::new(ProtonGame {
+            child,
+            game_pid,
+            exit_code_file: proton.exit_code_file,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    const CONFIG_INFO: &str = include_str!("../../../tests/fixtures/proton/config_info");
+
+    #[test]
+    fn parses_config_info() {
+        let (version, dir) = parse_config_info(CONFIG_INFO);
+        assert_eq!(version.as_deref(), Some("8.0-104"));
+        assert_eq!(
+            dir,
+            Some(PathBuf::from(
+                "/home/trucker/.steam/steam/steamapps/common/Proton 8.0"
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_old_config_info() {
+        let (version, dir) = parse_config_info(
+            "5.0-10\n/home/trucker/.steam/steam/steamapps/common/Proton 5.0/dist/share/fonts/\n",
+        );
+        assert_eq!(version.as_deref(), Some("5.0-10"));
+        assert_eq!(
+            dir,
+            Some(PathBuf::from(
+                "/home/trucker/.steam/steam/steamapps/common/Proton 5.0"
+            ))
+        );
+
+        assert_eq!(parse_config_info(""), (None, None));
+    }
+
+    #[test]
+    fn converts_to_wine_paths() {
+        assert_eq!(
+            to_wine_path(Path::new(
+                "/mnt/games/steamapps/common/Euro Truck Simulator 2/bin/win_x64/eurotrucks2.exe"
+            )),
+            "Z:\\mnt\\games\\steamapps\\common\\Euro Truck Simulator 2\\bin\\win_x64\\eurotrucks2.exe"
+        );
+    }
+
+    #[test]
+    fn builds_proton_command() {
+        let proton = Proton {
+            dir: PathBuf::from("/steam/steamapps/common/Proton 8.0"),
+            compat_data: PathBuf::from("/games/steamapps/compatdata/270880"),
+            steam_dir: PathBuf::from("/steam"),
+            cli: PathBuf::from("/data/truckersmp-cli/truckersmp-cli.exe"),
+            exit_code_file: PathBuf::from("/tmp/truckersmp-cli-exit-code-42"),
+        };
+        let game_path =
+            Path::new("/games/steamapps/common/American Truck Simulator/bin/win_x64/amtrucks.exe");
+        let args = [
+            "-nointro".to_string(),
+            "-homedir".to_string(),
+            "C:\\ats profiles".to_string(),
+        ];
+        let dlls = [PathBuf::from("/data/truckersmp-cli/content/core_atsmp.dll")];
+
+        let command = proton.command(&LaunchOptions {
+            game: Game::ATS,
+            game_path,
+            args: &args,
+            dlls: &dlls,
+            env: &HashMap::new(),
+        });
+
+        assert_eq!(
+            command.program,
+            PathBuf::from("/steam/steamapps/common/Proton 8.0/proton")
+        );
+        assert_eq!(
+            command.args,
+            [
+                "waitforexitandrun",
+                "/data/truckersmp-cli/truckersmp-cli.exe",
+                "inject",
+                "--game",
+                "ats",
+                "--wait",
+                "--exit-code-file",
+                "Z:\\tmp\\truckersmp-cli-exit-code-42",
+                "--dll",
+                "Z:\\data\\truckersmp-cli\\content\\core_atsmp.dll",
+                "Z:\\games\\steamapps\\common\\American Truck Simulator\\bin\\win_x64\\amtrucks.exe",
+                "--",
+                "-nointro",
+                "-homedir",
+                "C:\\ats profiles",
+            ]
+        );
+        assert!(command.env.contains(&(
+            "STEAM_COMPAT_DATA_PATH".to_string(),
+            "/games/steamapps/compatdata/270880".to_string()
+        )));
+        assert!(
+            command
+                .env
+                .contains(&("SteamAppId".to_string(), "270880".to_string()))
+        );
+    }
+
+    #[test]
+    fn leaves_out_empty_game_args() {
+        let proton = Proton {
+            dir: PathBuf::from("/proton"),
+            compat_data: PathBuf::from("/compatdata/227300"),
+            steam_dir: PathBuf::from("/steam"),
+            cli: PathBuf::from("/cli.exe"),
+            exit_code_file: PathBuf::from("/tmp/exit-code"),
+        };
+
+        let command = proton.command(&LaunchOptions {
+            game: Game::ETS2,
+            game_path: Path::new("/ets2/bin/win_x64/eurotrucks2.exe"),
+            args: &[],
+            dlls: &[],
+            env: &HashMap::new(),
+        });
+
+        assert_eq!(
+            command.args.last().unwrap(),
+            "Z:\\ets2\\bin\\win_x64\\eurotrucks2.exe"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    ffi::{OsStr, c_void},
+    os::windows::ffi::OsStrExt as _,
+    path::Path,
+};
+
+use windows::{
+    Win32::{
+        Foundation::{CloseHandle, HANDLE, WAIT_OBJECT_0, WAIT_TIMEOUT},
+        System::{
+            Diagnostics::Debug::WriteProcessMemory,
+            LibraryLoader::{GetModuleHandleA, GetProcAddress},
+            Memory::{
+                MEM_COMMIT, MEM_RELEASE, MEM_RESERVE, PAGE_READWRITE, VirtualAllocEx, VirtualFreeEx,
+            },
+            Threading::{
+                CREATE_SUSPENDED, CreateProcessW, CreateRemoteThread, GetExitCodeProcess,
+                GetExitCodeThread, INFINITE, PROCESS_INFORMATION, ResumeThread, STARTUPINFOW,
+                TerminateProcess, WaitForSingleObject,
+            },
+        },
+    },
+    core::{PCWSTR, PWSTR, s},
+};
+
+use super::{LaunchOptions, LaunchedGame, Launcher, command_line::build_command_line};
+use crate::{
+    cmd::kill::is_module_loaded,
+    errors::{Error, TResult},
+};
+
+// how long a dll gets to load before we give up on it
+const INJECTION_TIMEOUT_MS: u32 = 30_000;
+
+pub struct Win32Launcher;
+
+struct Win32Game {
+    process: HANDLE,
+    pid: u32,
+}
+
+impl LaunchedGame for Win32Game {
+    fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    // this blocks the whole thread, but there's nothing else left to do anyway
+    fn wait(self: Box<Self>) -> TResult<u32> {
+        let mut exit_code = 0;
+
+        unsafe {
+            WaitForSingleObject(self.process, INFINITE);
+            GetExitCodeProcess(self.process, &mut exit_code)
+                .map_err(|_| Error::UnknownGameExitCode)?;
+        }
+
+        Ok(exit_code)
+    }
+
+    // dropping it is all it takes
+    fn detach(self: Box<Self>) {}
+}
+
+// however we stop caring about the game, the handle has to go
+impl Drop for Win32Game {
+    fn drop(&mut self) {
+        unsafe { CloseHandle(self.process).ok() };
+    }
+}
+
+impl Launcher for Win32Launcher {
+    /// Starts the game suspended, injects the dlls and lets it run
+    fn launch(&self, options: &LaunchOptions) -> TResult<Box<dyn LaunchedGame>> {
+        let game_path = options.game_path;
+        let path: Vec<u16> = game_path.as_os_str().encode_wide().chain(Some(0)).collect(); // uft16
+
+        // CreateProcessW wants a mutable buffer for the command line
+        let mut command_line: Vec<u16> = OsStr::new(&build_command_line(game_path, options.args))
+            .encode_wide()
+            .chain(Some(0))
+            .collect();
+
+        // the game expects to be started from its own bin folder
+        let working_dir: Vec<u16> = game_path
+            .parent()
+            .unwrap_or(Path::new("."))
+            .as_os_str()
+            .encode_wide()
+            .chain(Some(0))
+            .collect();
+
+        unsafe {
+            std::env::set_var("SteamGameId", format!("{}", options.game as u32));
+            std::env::set_var("SteamAppId", format!("{}", options.game as u32));
+            // the game inherits our environment
+            for (key, value) in options.env {
+                std::env::set_var(key, value);
+            }
+
+            let mut startup_info: STARTUPINFOW = std::mem::zeroed();
+            startup_info.cb = std::mem::size_of::<STARTUPINFOW>() as u32;
+            let mut process_info: PROCESS_INFORMATION = std::mem::zeroed();
+
+            CreateProcessW(
+                PCWSTR(path.as_ptr()),
+                Some(PWSTR(command_line.as_mut_ptr())),
+                None,
+                None,
+                false,
+                CREATE_SUSPENDED,
+                None,
+                PCWSTR(working_dir.as_ptr()),
+                &startup_info,
+                &mut process_info,
+            )
+            .map_err(|_| Error::FailedGameLaunch)?;
+
+            for dll in options.dlls {
+                if let Err(e) = inject_dll(process_info.hProcess, process_info.dwProcessId, dll) {
+                    // a game left suspended would just get in the way of the next launch
+                    TerminateProcess(process_info.hProcess, 1).ok();
+                    CloseHandle(process_info.hThread).ok();
+                    CloseHandle(process_info.hProcess).ok();
+                    return Err(e);
+                }
+            }
+            ResumeThread(process_info.hThread);
+            CloseHandle(process_info.hThread).ok();
+
+            Ok(Box::new(Win32Game {
+                process: process_info.hProcess,
+                pid: process_info.dwProcessId,
+            }))
+        }
+    }
+}
+
+// your typical remote thread dll or shellcode injection lol
+fn inject_dll(process: HANDLE, pid: u32, dll: &Path) -> TResult<()> {
+    let dll_path: Vec<u16> = dll.as_os_str().encode_wide().chain(Some(0)).collect(); // uft16
+    let dll_path_len = dll_path.len() * std::mem::size_of::<u16>();
+
+    let alloc_addr = unsafe {
+        VirtualAllocEx(
+            process,
+            None,
+            dll_path_len,
+            MEM_COMMIT | MEM_RESERVE,
+            PAGE_READWRITE,
+        )
+    };
+    if alloc_addr.is_null() {
+        return Err(Error::InjectionAllocFailed);
+    }
+
+    let result = load_remote_library(process, pid, alloc_addr, &dll_path, dll);
+    // the path only has to be around until LoadLibraryW is done with it
+    unsafe { VirtualFreeEx(process, alloc_addr, 0, MEM_RELEASE).ok() };
+
+    result
+}
+
+fn load_remote_library(
+    process: HANDLE,
+    pid: u32,
+    alloc_addr: *mut c_void,
+    dll_path: &[u16],
+    dll: &Path,
+) -> TResult<()> {
+    unsafe {
+        WriteProcessMemory(
+            process,
+            alloc_addr,
+            dll_path.as_ptr() as *const _,
+            std::mem::size_of_val(dll_path),
+            None,
+        )
+        .map_err(|_| Error::InjectionWriteFailed)?;
+
+        let kernel_handle =
+            GetModuleHandleA(s!("kernel32.dll")).map_err(|_| Error::LoadLibraryNotFound)?;
+        let load_library_addr =
+            GetProcAddress(kernel_handle, s!("LoadLibraryW")).ok_or(Error::LoadLibraryNotFound)?;
+
+        let remote_thread = CreateRemoteThread(
+            process,
+            None,
+            0,
+            // stupid type signature
+            Some(std::mem::transmute::<
+                unsafe extern "system" fn() -> isize,
+                unsafe extern "system" fn(*mut c_void) -> u32,
+            >(load_library_addr)),
+            Some(alloc_addr),
+            0,
+            None,
+        )
+        .map_err(|_| Error::RemoteThreadFailed)?;
+
+        let wait = WaitForSingleObject(remote_thread, INJECTION_TIMEOUT_MS);
+        // LoadLibraryW's return value ends up as the thread's exit code, but only the lower
+        // 32 bits of it. a dll loaded at a multiple of 4 GiB looks just like a failed load
+        let mut module = 0;
+        let exit_code = GetExitCodeThread(remote_thread, &mut module);
+        CloseHandle(remote_thread).ok();
+
+        if wait == WAIT_TIMEOUT {
+            return Err(Error::InjectionTimedOut(dll.to_path_buf()));
+        }
+        if wait != WAIT_OBJECT_0 || exit_code.is_err() {
+            return Err(Error::InjectionWaitFailed);
+        }
+        if module == 0 {
+            let name = dll.file_name().unwrap_or_default().to_string_lossy();
+            if is_module_loaded(pid, &name) != Some(true) {
+                return Err(Error::DllLoadFailed(dll.to_path_buf()));
+            }
+        }
+    }
+
+    Ok(())
+}
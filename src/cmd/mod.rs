@@ -1,3 +1,5 @@
+use std::{net::SocketAddr, path::PathBuf};
+
 use clap::{
     Parser, Subcommand,
     builder::{IntoResettable, StyledStr},
@@ -5,11 +7,20 @@ use clap::{
 
 use crate::{cmd::game::server::ServerInfoType, errors::TResult, game::Game};
 
+mod bundle;
+mod compat;
+mod content;
 mod game;
+mod hooks;
+mod inject;
 mod kill;
+mod launch;
 mod mod_version;
 mod run;
+mod session;
+mod stats;
 mod update;
+mod verify;
 
 struct HelpTemplate;
 
@@ -39,20 +50,29 @@ pub trait Run {
 #[clap(version, about, author, propagate_version = true, help_template = HelpTemplate)]
 pub enum Cmd {
     Update(Update),
+    Verify(Verify),
     Run(RunGame),
     Kill(Kill),
     Version(ModVersion),
     Game(GameCmd),
+    Content(ContentCmd),
+    Stats(Stats),
+    #[clap(hide = true)]
+    Inject(InjectGame),
 }
 
 impl Run for Cmd {
     async fn run(&self) -> TResult<()> {
         match self {
             Cmd::Update(update) => update.run().await,
+            Cmd::Verify(cmd) => cmd.run().await,
             Cmd::Run(run) => run.run().await,
             Cmd::Kill(kill) => kill.run().await,
             Cmd::Version(cmd) => cmd.run().await,
             Cmd::Game(cmd) => cmd.run().await,
+            Cmd::Content(cmd) => cmd.run().await,
+            Cmd::Stats(cmd) => cmd.run().await,
+            Cmd::Inject(cmd) => cmd.run().await,
         }
     }
 }
@@ -80,6 +100,23 @@ pub struct Update {
     /// The number of retries to do before giving up on downloading a file.
     #[clap(short, long, default_value_t = 3)]
     retry_count: u32,
+
+    /// Install the mod files from a local directory or a zip/tar archive instead of downloading them
+    #[clap(short, long)]
+    from: Option<PathBuf>,
+}
+
+/// Verify the TruckersMP mod files without downloading anything
+#[derive(Debug, Parser)]
+#[clap(author, help_template = HelpTemplate)]
+pub struct Verify {
+    /// The game to verify the mod files for
+    #[clap(short, long, value_enum)]
+    game: Option<Game>,
+
+    /// Whether to print the report as JSON
+    #[clap(short, long, default_value_t = false)]
+    json: bool,
 }
 
 /// Run the TruckersMP mod for the optionally specified game
@@ -89,9 +126,56 @@ pub struct RunGame {
     /// The game to be played
     #[clap(short, long, value_enum)]
     game: Option<Game>,
+    /// The launch profile from the config to use
+    #[clap(short, long)]
+    profile: Option<String>,
     /// Whether to not verify the mod files before running the game
     #[clap(short = 'v', long, default_value_t = false)]
     no_verify: bool,
+    /// Whether to wait for the game to exit, report how the session went and record it for the stats
+    #[clap(short, long, default_value_t = false)]
+    wait: bool,
+    /// The game's install folder, for when Steam doesn't know where it is
+    #[clap(long)]
+    game_path: Option<PathBuf>,
+    /// Launch even if TruckersMP doesn't support the installed game version
+    #[clap(long, default_value_t = false)]
+    force: bool,
+    /// An extra 64-bit dll to load into the game after TruckersMP, can be given more than once
+    #[clap(long = "inject")]
+    inject: Vec<PathBuf>,
+    /// Kill the game first if it's already running, instead of refusing to launch
+    #[clap(long, default_value_t = false)]
+    replace: bool,
+
+    /// Extra arguments for the game, passed after the ones from the config. Goes after `--`
+    #[clap(last = true)]
+    args: Vec<String>,
+}
+
+/// Start a game with the given dlls injected into it. Used inside Proton by the Linux build
+#[derive(Debug, Parser)]
+#[clap(author, help_template = HelpTemplate)]
+pub struct InjectGame {
+    /// The game being started
+    #[clap(short, long, value_enum)]
+    game: Game,
+    /// A dll to inject, in the order given
+    #[clap(long = "dll")]
+    dlls: Vec<PathBuf>,
+    /// Whether to wait for the game to exit and exit with its exit code
+    #[clap(short, long, default_value_t = false)]
+    wait: bool,
+    /// Also write the game's exit code here when waiting, as exit codes only make it out of
+    /// Wine cut down to 8 bits
+    #[clap(long)]
+    exit_code_file: Option<PathBuf>,
+    /// The game's executable
+    exe: PathBuf,
+
+    /// Arguments for the game. Goes after `--`
+    #[clap(last = true)]
+    args: Vec<String>,
 }
 
 /// Kill a game process if its running
@@ -101,12 +185,24 @@ pub struct Kill {
     /// The game to be killed
     #[clap(short, long, value_enum)]
     game: Option<Game>,
+    /// The game's install folder, for when Steam doesn't know where it is
+    #[clap(long)]
+    game_path: Option<PathBuf>,
 }
 
+/// Show your TruckersMP playtime and crash counts, from the sessions recorded by `run --wait`
+#[derive(Debug, Parser)]
+#[clap(author, help_template = HelpTemplate)]
+pub struct Stats;
+
 /// Get the current mod version with the supported game versions
 #[derive(Debug, Parser)]
 #[clap(author, help_template = HelpTemplate)]
-pub struct ModVersion;
+pub struct ModVersion {
+    /// Whether to skip the cached TruckersMP API responses
+    #[clap(long, default_value_t = false)]
+    no_cache: bool,
+}
 
 /// Get game related information.
 #[derive(Debug, Parser)]
@@ -119,6 +215,9 @@ pub struct GameCmd {
 #[derive(Debug, Subcommand, Clone)]
 pub enum GameCommand {
     Servers(ServerInfo),
+    Time(TimeInfo),
+    Rules(RulesInfo),
+    Player(PlayerInfo),
 }
 
 /// Get the current server info
@@ -143,4 +242,73 @@ pub struct ServerInfo {
     /// Whether to show additional info about the server
     #[clap(short, long, default_value_t = false)]
     additional: bool,
+
+    /// Whether to skip the cached TruckersMP API responses
+    #[clap(long, default_value_t = false)]
+    no_cache: bool,
+}
+
+/// Get the current in-game time on the TruckersMP servers
+#[derive(Debug, Parser, Clone)]
+#[clap(author, help_template = HelpTemplate)]
+pub struct TimeInfo {
+    /// Whether to skip the cached TruckersMP API responses
+    #[clap(long, default_value_t = false)]
+    no_cache: bool,
+}
+
+/// Get the current TruckersMP rules
+#[derive(Debug, Parser, Clone)]
+#[clap(author, help_template = HelpTemplate)]
+pub struct RulesInfo {
+    /// Whether to skip the cached TruckersMP API responses
+    #[clap(long, default_value_t = false)]
+    no_cache: bool,
+}
+
+/// Look up a TruckersMP player and their bans
+#[derive(Debug, Parser, Clone)]
+#[clap(author, help_template = HelpTemplate)]
+pub struct PlayerInfo {
+    /// The TruckersMP ID of the player
+    id: u64,
+
+    /// Whether to skip the cached TruckersMP API responses
+    #[clap(long, default_value_t = false)]
+    no_cache: bool,
+}
+
+/// Manage the local TruckersMP mod files.
+#[derive(Debug, Parser)]
+#[clap(author, help_template = HelpTemplate)]
+pub struct ContentCmd {
+    #[clap(subcommand)]
+    pub cmd: Option<ContentCommand>,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum ContentCommand {
+    Export(ContentExport),
+    Serve(ContentServe),
+}
+
+/// Export the verified mod files as a portable zip bundle
+#[derive(Debug, Parser, Clone)]
+#[clap(author, help_template = HelpTemplate)]
+pub struct ContentExport {
+    /// Where to write the bundle to
+    file: PathBuf,
+
+    /// The game to export the mod files for. Every fully verified game is exported if not specified
+    #[clap(short, long, value_enum)]
+    game: Option<Game>,
+}
+
+/// Serve the verified mod files over HTTP so other machines can use this one as a mirror
+#[derive(Debug, Parser, Clone)]
+#[clap(author, help_template = HelpTemplate)]
+pub struct ContentServe {
+    /// The address to listen on
+    #[clap(short, long, default_value = "0.0.0.0:8080")]
+    bind: SocketAddr,
 }
@@ -1,155 +1,205 @@
-use std::{os::windows::ffi::OsStrExt as _, path::PathBuf};
-
-use clap::crate_name;
-use dirs::data_dir;
-use windows::{
-    Win32::{
-        Foundation::{CloseHandle, HANDLE},
-        System::{
-            Diagnostics::Debug::WriteProcessMemory,
-            LibraryLoader::{GetModuleHandleA, GetProcAddress},
-            Memory::{MEM_COMMIT, MEM_RESERVE, PAGE_READWRITE, VirtualAllocEx},
-            Threading::{
-                CREATE_SUSPENDED, CreateProcessW, CreateRemoteThread, INFINITE,
-                PROCESS_INFORMATION, ResumeThread, STARTUPINFOW, WaitForSingleObject,
-            },
-        },
-    },
-    core::{PWSTR, s},
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
 };
 
+use sysinfo::Pid;
+
 use crate::{
-    cmd::{Run, RunGame, Update},
+    api::ApiClient,
+    cmd::{
+        Run, RunGame,
+        compat::check_game_version,
+        hooks::{Hook, HookContext, has_hook, run_hook},
+        kill::{find_game_process, has_truckersmp_loaded, scan_processes},
+        launch::{LaunchOptions, get_launcher},
+        session::{Session, format_duration, record_session, unix_now},
+        update::{UpdateOptions, get_content_dir, update_content},
+    },
+    config::{get_config, get_profile},
     errors::{Error, TResult},
-    game::{get_available_games, get_game_path, get_specific_game, get_steamworks_client},
+    game::{Game, get_game_path, pick_game_to_launch},
+    pe::is_64_bit,
+    state::update_state,
 };
 
 impl Run for RunGame {
-    async fn run(&self) -> crate::errors::TResult<()> {
-        let steamworks = get_steamworks_client()?;
-
-        let game = if let Some(game) = self.game {
-            get_specific_game(&steamworks, game)
-        } else {
-            get_available_games(&steamworks)
-        }?;
+    async fn run(&self) -> TResult<()> {
+        let profile = self.profile.as_deref().map(get_profile).transpose()?;
+        let (game, source) = pick_game_to_launch(
+            self.game.or(profile.and_then(|p| p.game)),
+            self.game_path.as_deref(),
+        )?;
+
+        let game_path = get_game_path(&*source, game)?;
+        // a second suspended game just ends up failing in weird ways once it's in-game
+        let running_game = find_running_game(game, &game_path, self.replace)?;
+        check_game_version(game, &game_path, self.force).await?;
+
+        let mut args = get_config()?.games.get(game).args.clone();
+        if let Some(profile) = profile {
+            args.extend(profile.args.iter().cloned());
+            if let Some(homedir) = &profile.homedir {
+                args.push("-homedir".to_string());
+                args.push(homedir.display().to_string());
+            }
+        }
+        args.extend(self.args.iter().cloned());
 
-        let game_path = get_game_path(&steamworks, game)?;
-        let path: Vec<u16> = game_path.as_os_str().encode_wide().chain(Some(0)).collect(); // uft16
+        let extra_dlls = get_extra_dlls(game, profile.map(|p| &p.inject[..]), &self.inject)?;
 
-        let content_dir = data_dir()
-            .ok_or_else(|| Error::NoAppdataPath)?
-            .join(crate_name!())
-            .join("content")
-            .to_path_buf(); // TODO: make this configurable
+        let content_dir = get_content_dir()?;
         let dll_path = content_dir.join(game.dll());
 
-        if !self.no_verify {
-            Update {
-                game: Some(game),
-                ..Update::default()
-            }
-            .run()
-            .await?;
+        // a post_exit hook needs us around until the game is gone
+        let wait = self.wait || has_hook(Hook::PostExit)?;
+
+        let mut hook_context = HookContext {
+            game,
+            game_path: Some(&game_path),
+            content_dir: &content_dir,
+            pid: None,
+            exit_code: None,
+        };
+        run_hook(Hook::PreLaunch, &hook_context).await?;
+
+        // only now that nothing else can call the launch off, and before the update touches
+        // files the running game might still have open
+        if let Some(pid) = running_game {
+            kill_running_game(game, pid)?;
+        }
+
+        let verify = !self.no_verify && profile.and_then(|p| p.verify).unwrap_or(true);
+        if verify {
+            update_content(game, &UpdateOptions::default()).await?;
         }
 
         if !dll_path.exists() {
-            return Err(Error::FailedInjectingDLL(
-                "failed to find the mod's dll file, you might want to update the mod files".into(),
-            ));
+            return Err(Error::DllNotFound(dll_path));
         }
+        // TruckersMP goes first, everything else loads on top of it
+        let dlls: Vec<PathBuf> = std::iter::once(dll_path).chain(extra_dlls).collect();
+
+        // only needed for the session history, so it's fine if the api is having a bad day
+        let mod_version = if wait {
+            ApiClient::new()?.version().await.ok().map(|v| v.version)
+        } else {
+            None
+        };
 
         println!("Launching {:?}!", game);
-        unsafe {
-            std::env::set_var("SteamGameId", format!("{}", game as u32));
-            std::env::set_var("SteamAppId", format!("{}", game as u32));
-
-            let mut startup_info: STARTUPINFOW = std::mem::zeroed();
-            startup_info.cb = std::mem::size_of::<STARTUPINFOW>() as u32;
-            let mut process_info: PROCESS_INFORMATION = std::mem::zeroed();
-
-            CreateProcessW(
-                None,
-                Some(PWSTR(path.as_ptr() as *mut u16)),
-                None,
-                None,
-                false,
-                CREATE_SUSPENDED,
-                None,
-                None,
-                &startup_info,
-                &mut process_info,
-            )
-            .map_err(|_| Error::FailedGameLaunch)?;
-
-            inject_dll(process_info.hProcess, dll_path)?;
-            ResumeThread(process_info.hThread);
-            CloseHandle(process_info.hThread).ok();
+        let no_env = HashMap::new();
+        let launched = get_launcher()?.launch(&LaunchOptions {
+            game,
+            game_path: &game_path,
+            args: &args,
+            dlls: &dlls,
+            env: profile.map_or(&no_env, |p| &p.env),
+        })?;
+        let pid = launched.pid();
+        let started_at = unix_now();
+        update_state(|state| state.last_game = Some(game))?;
+
+        hook_context.pid = Some(pid);
+        run_hook(Hook::PostLaunch, &hook_context).await?;
+
+        if !wait {
+            launched.detach();
+            return Ok(());
         }
-        Ok(())
-    }
-}
 
-// your typical remote thread dll or shellcode injection lol
-fn inject_dll(process: HANDLE, dll_path: PathBuf) -> TResult<()> {
-    let dll_path: Vec<u16> = dll_path.as_os_str().encode_wide().chain(Some(0)).collect(); // uft16
-    let dll_path_len = dll_path.len() * std::mem::size_of::<u16>();
-
-    unsafe {
-        let alloc_addr = VirtualAllocEx(
-            process,
-            None,
-            dll_path_len,
-            MEM_COMMIT | MEM_RESERVE,
-            PAGE_READWRITE,
+        println!("Waiting for {:?} to exit...", game);
+        let exit_code = launched.wait()?;
+        let session = Session {
+            game,
+            mod_version,
+            profile: self.profile.clone(),
+            started_at,
+            ended_at: unix_now(),
+            exit_code,
+        };
+        println!(
+            "{:?} exited with code {} after {}",
+            game,
+            exit_code,
+            format_duration(session.duration())
         );
+        record_session(&session).await?;
+
+        hook_context.exit_code = Some(exit_code);
+        run_hook(Hook::PostExit, &hook_context).await?;
 
-        if alloc_addr.is_null() {
-            CloseHandle(process).ok();
-            return Err(Error::FailedInjectingDLL(
-                "failed to allocate memory in the game process".into(),
-            ));
+        if exit_code != 0 {
+            return Err(Error::GameCrashed(game, exit_code));
         }
 
-        WriteProcessMemory(
-            process,
-            alloc_addr,
-            dll_path.as_ptr() as *const _,
-            dll_path_len,
-            None,
-        )
-        .map_err(|_| {
-            CloseHandle(process).ok();
-            Error::FailedInjectingDLL("failed to write to game process memory".into())
-        })?;
+        Ok(())
+    }
+}
 
-        let kernel_handle = GetModuleHandleA(s!("kernel32.dll")).map_err(|_| {
-            CloseHandle(process).ok();
-            Error::FailedInjectingDLL("failed to get kernel32 handle".into())
-        })?;
-        let load_library_addr =
-            GetProcAddress(kernel_handle, s!("LoadLibraryW")).ok_or_else(|| {
-                CloseHandle(process).ok();
-                Error::FailedInjectingDLL("failed to get LoadLibraryW addr".into())
-            })?;
-
-        let remote_thread = CreateRemoteThread(
-            process,
-            None,
-            0,
-            // stupid type signature
-            Some(std::mem::transmute(load_library_addr)),
-            Some(alloc_addr),
-            0,
-            None,
-        )
-        .map_err(|_| {
-            CloseHandle(process).ok();
-            Error::FailedInjectingDLL("failed to create remote thread into game process".into())
-        })?;
+/// Collects the extra dlls from the config, the profile and `--inject` in that order, making
+/// sure they're all there and loadable before anything gets launched
+fn get_extra_dlls(
+    game: Game,
+    profile: Option<&[PathBuf]>,
+    cli: &[PathBuf],
+) -> TResult<Vec<PathBuf>> {
+    let config = &get_config()?.games.get(game).inject;
+
+    config
+        .iter()
+        .chain(profile.unwrap_or_default())
+        .chain(cli)
+        .map(|dll| {
+            if !dll.is_file() {
+                return Err(Error::ExtraDllNotFound(dll.clone()));
+            }
+            if !is_64_bit(dll)? {
+                return Err(Error::ExtraDllNot64Bit(dll.clone()));
+            }
+            // relative paths wouldn't survive the game's own working directory
+            Ok(std::path::absolute(dll)?)
+        })
+        .collect()
+}
 
-        WaitForSingleObject(remote_thread, INFINITE);
-        CloseHandle(process).ok();
+/// Returns the pid of the game that's already running, if `replace` says it can be killed
+fn find_running_game(game: Game, game_path: &Path, replace: bool) -> TResult<Option<Pid>> {
+    let sysinfo = scan_processes();
+    let Some(process) = find_game_process(&sysinfo, game, game_path) else {
+        return Ok(None);
+    };
+
+    let truckersmp = match has_truckersmp_loaded(game, process.pid()) {
+        Some(true) => "with TruckersMP loaded",
+        Some(false) => "without TruckersMP",
+        None => "but couldn't tell whether TruckersMP is loaded in it",
+    };
+    println!(
+        "{:?} is already running (pid {}) {}",
+        game,
+        process.pid(),
+        truckersmp
+    );
+
+    if !replace {
+        return Err(Error::GameAlreadyRunning(game));
     }
+
+    Ok(Some(process.pid()))
+}
+
+fn kill_running_game(game: Game, pid: Pid) -> TResult<()> {
+    let sysinfo = scan_processes();
+    // it might have been closed in the meantime
+    let Some(process) = sysinfo.process(pid) else {
+        return Ok(());
+    };
+
+    println!("Killing the running {:?} first...", game);
+    process
+        .kill_and_wait()
+        .map_err(|_| Error::FailedKillingGame(game))?;
+
     Ok(())
 }
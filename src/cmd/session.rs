@@ -0,0 +1,82 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tokio::{
+    fs::{self, OpenOptions},
+    io::AsyncWriteExt as _,
+};
+
+use crate::{cmd::update::get_app_dir, errors::TResult, game::Game};
+
+/// A finished play session, stored one per line in `sessions.jsonl`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(super) struct Session {
+    pub(super) game: Game,
+    pub(super) mod_version: Option<String>,
+    pub(super) profile: Option<String>,
+    /// Unix timestamp in seconds
+    pub(super) started_at: u64,
+    /// Unix timestamp in seconds
+    pub(super) ended_at: u64,
+    pub(super) exit_code: u32,
+}
+
+impl Session {
+    pub(super) fn duration(&self) -> Duration {
+        Duration::from_secs(self.ended_at.saturating_sub(self.started_at))
+    }
+
+    pub(super) fn crashed(&self) -> bool {
+        self.exit_code != 0
+    }
+}
+
+fn get_sessions_path() -> TResult<PathBuf> {
+    Ok(get_app_dir()?.join("sessions.jsonl"))
+}
+
+pub(super) async fn record_session(session: &Session) -> TResult<()> {
+    let path = get_sessions_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    let mut line = serde_json::to_string(session)?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(line.as_bytes()).await?;
+    Ok(())
+}
+
+pub(super) async fn read_sessions() -> TResult<Vec<Session>> {
+    let path = get_sessions_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    // a half written line shouldn't make the whole history unreadable
+    Ok(fs::read_to_string(path)
+        .await?
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+pub(super) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+pub(super) fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}h {:02}m {:02}s", secs / 3600, secs / 60 % 60, secs % 60)
+}
@@ -0,0 +1,66 @@
+use comfy_table::{Cell, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+
+use crate::{
+    cmd::{
+        Run, Stats,
+        session::{format_duration, read_sessions, unix_now},
+    },
+    errors::TResult,
+    game::Game,
+};
+
+const WEEK_SECS: u64 = 7 * 24 * 60 * 60;
+
+impl Run for Stats {
+    async fn run(&self) -> TResult<()> {
+        let sessions = read_sessions().await?;
+        if sessions.is_empty() {
+            println!("No sessions recorded yet. Launch the game with `run --wait` to record them");
+            return Ok(());
+        }
+
+        let week_ago = unix_now().saturating_sub(WEEK_SECS);
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_header(vec![
+                "Game",
+                "Sessions",
+                "Total Playtime",
+                "Last 7 Days",
+                "Crashes",
+            ]);
+
+        for game in [Game::ETS2, Game::ATS] {
+            let sessions = sessions
+                .iter()
+                .filter(|s| s.game == game)
+                .collect::<Vec<_>>();
+            if sessions.is_empty() {
+                continue;
+            }
+
+            let total = sessions.iter().map(|s| s.duration()).sum();
+            let weekly = sessions
+                .iter()
+                .filter(|s| s.started_at >= week_ago)
+                .map(|s| s.duration())
+                .sum();
+            let crashes = sessions.iter().filter(|s| s.crashed()).count();
+
+            table.add_row(vec![
+                Cell::new(format!("{:?}", game)),
+                Cell::new(sessions.len()),
+                Cell::new(format_duration(total)),
+                Cell::new(format_duration(weekly)),
+                Cell::new(crashes),
+            ]);
+        }
+
+        println!("{table}");
+
+        Ok(())
+    }
+}
@@ -1,27 +1,68 @@
-use std::{ffi::OsStr, fmt::Write, path::PathBuf, sync::Arc, u8};
+use std::{
+    ffi::OsStr,
+    fmt::Write,
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use clap::{crate_name, crate_version};
+use clap::crate_name;
 use dirs::data_dir;
-use futures_util::{StreamExt as _, future::join_all};
+use futures_util::{
+    StreamExt as _,
+    future::{join_all, try_join, try_join_all},
+};
 use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle};
 use once_cell::sync::Lazy;
+use reqwest::{
+    Response, StatusCode,
+    header::{ACCEPT_ENCODING, CONTENT_RANGE, RANGE},
+};
 use reqwest_middleware::ClientWithMiddleware;
 use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
 use tokio::{
-    fs,
-    io::{AsyncReadExt as _, AsyncWriteExt as _, BufReader},
+    fs::{self, OpenOptions},
+    io::{AsyncReadExt as _, AsyncSeekExt as _, AsyncWriteExt as _, BufReader},
     sync::Semaphore,
 };
 
 use crate::{
-    cmd::{Run, Update},
+    cmd::{
+        Run, Update,
+        bundle::install_from,
+        hooks::{Hook, HookContext, run_hook},
+    },
+    config::get_config,
     errors::{Error, TResult},
-    game::{Game, get_available_games, get_specific_game, get_steamworks_client},
+    game::{Game, pick_game},
+    http::client_builder,
 };
 
 const UPDATE_URL: &str = "https://update.ets2mp.com/files.json";
 const DOWNLOAD_URL: &str = "https://download-new.ets2mp.com/files/";
 
+/// Returns the files.json and download urls, pointing at the configured mirror if there's one
+fn get_content_urls() -> TResult<(String, String)> {
+    let urls = match &get_config()?.content.mirror {
+        // a mirror has the same layout, see `content serve`
+        Some(mirror) => {
+            let mirror = mirror.trim_end_matches('/');
+            (format!("{mirror}/files.json"), format!("{mirror}/files/"))
+        }
+        None => (UPDATE_URL.to_string(), DOWNLOAD_URL.to_string()),
+    };
+
+    Ok(urls)
+}
+
+// files bigger than this are downloaded in parallel ranged chunks
+const CHUNKED_DOWNLOAD_THRESHOLD: u64 = 16 * 1024 * 1024;
+const DOWNLOAD_CHUNK_COUNT: u64 = 4;
+// what the first request asks for, smaller files come in one go
+const FIRST_RANGE_SIZE: u64 = CHUNKED_DOWNLOAD_THRESHOLD / DOWNLOAD_CHUNK_COUNT;
+// a file that still doesn't match after this many downloads isn't going to
+const MAX_DOWNLOAD_ATTEMPTS: usize = 3;
+
 static PROGRESS_BAR_TEMPLATE: Lazy<ProgressStyle> = Lazy::new(|| {
     ProgressStyle::with_template("{spinner:.green} {msg} [{wide_bar:.cyan/blue}] {percent}% {eta}")
         .unwrap()
@@ -33,59 +74,111 @@ static PROGRESS_BAR_TEMPLATE: Lazy<ProgressStyle> = Lazy::new(|| {
 
 impl Run for Update {
     async fn run(&self) -> crate::errors::TResult<()> {
-        let steamworks = get_steamworks_client()?;
-
-        let reqwest_retry_policy =
-            ExponentialBackoff::builder().build_with_max_retries(self.retry_count);
-        let reqwest_client = reqwest::Client::builder()
-            .user_agent(format!("Yet Another TruckersMP Cli/{:?}", crate_version!()))
-            .build()?;
-        let reqwest_client = reqwest_middleware::ClientBuilder::new(reqwest_client)
-            .with(RetryTransientMiddleware::new_with_policy(
-                reqwest_retry_policy,
-            ))
-            .build();
-        let content_files = get_content_files(&reqwest_client).await?;
-
-        let game = if let Some(game) = self.game {
-            get_specific_game(&steamworks, game)
-        } else {
-            get_available_games(&steamworks)
-        }?;
-
-        let content_dir = data_dir()
-            .ok_or_else(|| Error::NoAppdataPath)?
-            .join(crate_name!())
-            .join("content")
-            .to_path_buf(); // TODO: make this configurable
-
-        // I could get the parent folder but that opens the risk of me accidentally deleting the whole system32 folder lol.
-        if self.clean && content_dir.exists() {
-            fs::remove_dir_all(&content_dir).await?;
-            fs::create_dir_all(&content_dir).await?;
-        }
+        // a game given by hand is taken as-is, there's nothing to look up for the mod files
+        let game = match self.game {
+            Some(game) => game,
+            None => pick_game(None, None)?.0,
+        };
+
+        update_content(
+            game,
+            &UpdateOptions {
+                clean: self.clean,
+                no_verify: self.no_verify,
+                retry_count: self.retry_count,
+                from: self.from.as_deref(),
+            },
+        )
+        .await
+    }
+}
 
-        let mut files = content_files.shared.clone();
-        match game {
-            Game::ETS2 => files.extend(content_files.ets2),
-            Game::ATS => files.extend(content_files.ats),
-        }
+#[derive(Default)]
+pub(super) struct UpdateOptions<'a> {
+    pub clean: bool,
+    pub no_verify: bool,
+    pub retry_count: u32,
+    pub from: Option<&'a Path>,
+}
+
+/// Brings the mod files for `game` up to date, running the update hooks around it
+pub(super) async fn update_content(game: Game, options: &UpdateOptions<'_>) -> TResult<()> {
+    let content_dir = get_content_dir()?;
+
+    // I could get the parent folder but that opens the risk of me accidentally deleting the whole system32 folder lol.
+    if options.clean && content_dir.exists() {
+        fs::remove_dir_all(&content_dir).await?;
+        fs::create_dir_all(&content_dir).await?;
+    }
+
+    let hook_context = HookContext {
+        game,
+        game_path: None,
+        content_dir: &content_dir,
+        pid: None,
+        exit_code: None,
+    };
+    run_hook(Hook::PreUpdate, &hook_context).await?;
+
+    if let Some(from) = options.from {
+        println!(
+            "Installing TruckersMP mod files for {:?} from {}",
+            game,
+            from.display()
+        );
+        fs::create_dir_all(&content_dir).await?;
+        install_from(from, game, &content_dir).await?;
+    } else {
+        let reqwest_client = build_reqwest_client(options.retry_count)?;
+        let files = get_content_files(&reqwest_client).await?.for_game(game);
 
         println!("Updating TruckersMP mod files for {:?}", game);
 
-        if !content_dir.exists() || self.clean {
+        let download_first = !content_dir.exists() || options.clean;
+        if download_first {
             tokio::fs::create_dir_all(&content_dir).await?;
-            verify_and_download(&reqwest_client, &files, content_dir, true, self.no_verify).await?
-        } else {
-            verify_and_download(&reqwest_client, &files, content_dir, false, self.no_verify).await?
         }
-
-        Ok(())
+        verify_and_download(
+            &reqwest_client,
+            &files,
+            content_dir.clone(),
+            download_first,
+            options.no_verify,
+        )
+        .await?
     }
+
+    run_hook(Hook::PostUpdate, &hook_context).await
+}
+
+pub(super) fn build_reqwest_client(retry_count: u32) -> TResult<ClientWithMiddleware> {
+    let reqwest_retry_policy = ExponentialBackoff::builder().build_with_max_retries(retry_count);
+    let reqwest_client = client_builder()?.build()?;
+
+    Ok(reqwest_middleware::ClientBuilder::new(reqwest_client)
+        .with(RetryTransientMiddleware::new_with_policy(
+            reqwest_retry_policy,
+        ))
+        .build())
+}
+
+pub(super) fn get_app_dir() -> TResult<PathBuf> {
+    Ok(data_dir()
+        .ok_or_else(|| Error::NoAppdataPath)?
+        .join(crate_name!()))
+}
+
+pub(super) fn get_content_dir() -> TResult<PathBuf> {
+    Ok(get_app_dir()?.join("content")) // TODO: make this configurable
+}
+
+/// Where the last fetched `files.json` is kept around for offline installs
+pub(super) fn get_cached_manifest_path() -> TResult<PathBuf> {
+    Ok(get_app_dir()?.join("files.json"))
 }
 
 #[derive(Debug, Clone)]
-struct ContentFiles {
+pub(super) struct ContentFiles {
     ets2: Vec<ContentFile>,
     ats: Vec<ContentFile>,
     shared: Vec<ContentFile>,
@@ -109,10 +202,30 @@ impl From<RawContentFiles> for ContentFiles {
     }
 }
 
+impl ContentFiles {
+    /// The shared files plus the ones specific to the given game
+    pub(super) fn for_game(&self, game: Game) -> Vec<ContentFile> {
+        let mut files = self.shared.clone();
+        match game {
+            Game::ETS2 => files.extend(self.ets2.iter().cloned()),
+            Game::ATS => files.extend(self.ats.iter().cloned()),
+        }
+        files
+    }
+
+    /// Every file in the manifest, no matter the game
+    pub(super) fn all(&self) -> Vec<ContentFile> {
+        let mut files = self.shared.clone();
+        files.extend(self.ets2.iter().cloned());
+        files.extend(self.ats.iter().cloned());
+        files
+    }
+}
+
 #[derive(Debug, Clone)]
-struct ContentFile {
-    md5: String,
-    file_path: String,
+pub(super) struct ContentFile {
+    pub(super) md5: String,
+    pub(super) file_path: String,
 }
 
 impl From<RawContentFile> for ContentFile {
@@ -145,6 +258,7 @@ struct RawContentFile {
 }
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[allow(clippy::upper_case_acronyms)]
 enum RawContentType {
     #[serde(rename = "ets2")]
     ETS2,
@@ -154,18 +268,51 @@ enum RawContentType {
     System,
 }
 
-async fn get_content_files(client: &ClientWithMiddleware) -> TResult<ContentFiles> {
-    let raw_content_files: RawContentFiles = client.get(UPDATE_URL).send().await?.json().await?;
+pub(super) async fn get_content_files(client: &ClientWithMiddleware) -> TResult<ContentFiles> {
+    let (update_url, _) = get_content_urls()?;
+    let manifest = client.get(update_url).send().await?.text().await?;
+    let content_files = parse_content_files(&manifest)?;
+
+    let cached_manifest = get_cached_manifest_path()?;
+    if let Some(parent) = cached_manifest.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::write(cached_manifest, manifest).await?;
+
+    Ok(content_files)
+}
+
+pub(super) fn parse_content_files(manifest: &str) -> TResult<ContentFiles> {
+    let raw_content_files: RawContentFiles = serde_json::from_str(manifest)?;
 
     Ok(ContentFiles::from(raw_content_files))
 }
 
+/// Drops the files `keep` says no to from a files.json, leaving everything else in it as is
+pub(super) fn filter_manifest(manifest: &str, keep: impl Fn(&str) -> bool) -> TResult<String> {
+    let mut manifest: serde_json::Value = serde_json::from_str(manifest)?;
+
+    if let Some(files) = manifest
+        .get_mut("Files")
+        .and_then(serde_json::Value::as_array_mut)
+    {
+        files.retain(|file| {
+            file.get("FilePath")
+                .and_then(serde_json::Value::as_str)
+                .is_some_and(|path| keep(path.strip_prefix('/').unwrap_or(path)))
+        });
+    }
+
+    Ok(serde_json::to_string(&manifest)?)
+}
+
 async fn download_files(
     client: &ClientWithMiddleware,
-    content_files: &Vec<ContentFile>,
-    content_dir: &PathBuf,
+    content_files: &[ContentFile],
+    content_dir: &Path,
 ) -> TResult<()> {
     let concurrency = Arc::new(Semaphore::new(8)); // todo: clap config
+    let (_, download_url) = get_content_urls()?;
     let progress_bars = MultiProgress::new();
 
     let main_pb = progress_bars.add(ProgressBar::new(content_files.len() as u64));
@@ -176,12 +323,12 @@ async fn download_files(
         .canonicalize()
         .expect("Failed horribly to canonicalize content dir");
 
-    let handles = content_files.into_iter().map(|file| {
+    let handles = content_files.iter().map(|file| {
         let progress_bar = progress_bars.clone();
         let progress_bar_style = PROGRESS_BAR_TEMPLATE.clone();
         let client = client.clone();
         let concurrency = concurrency.clone();
-        let url = format!("{DOWNLOAD_URL}{}", file.file_path);
+        let url = format!("{download_url}{}", file.file_path);
         let path = content_dir.join(&file.file_path);
         let main_pb = main_pb.clone();
 
@@ -192,9 +339,35 @@ async fn download_files(
                 tokio::fs::create_dir_all(parent).await?;
             }
 
-            let resp = client.get(url).send().await?;
-            let content_size = resp.content_length().unwrap_or(0);
-
+            // asking for the first range straight away tells us the size and whether ranges
+            // work at all, without a request just for the headers
+            let resp = client
+                .get(&url)
+                .header(RANGE, format!("bytes=0-{}", FIRST_RANGE_SIZE - 1))
+                // the byte offsets have to match the file, not some gzipped version of it
+                .header(ACCEPT_ENCODING, "identity")
+                .send()
+                .await?;
+            let content_range = match resp.status() {
+                StatusCode::PARTIAL_CONTENT => parse_content_range(&resp),
+                _ => None,
+            };
+            // empty files can't have a range, and a range we can't read is no use either
+            let resp = match resp.status() {
+                StatusCode::RANGE_NOT_SATISFIABLE | StatusCode::PARTIAL_CONTENT
+                    if content_range.is_none() =>
+                {
+                    client.get(&url).send().await?
+                }
+                _ => resp,
+            };
+            // a 404 page makes for a terrible dll
+            let resp = resp.error_for_status()?;
+
+            let content_size = match content_range {
+                Some((_, total)) => total,
+                None => resp.content_length().unwrap_or(0),
+            };
             let progress_bar = progress_bar.add(ProgressBar::new(content_size));
             progress_bar.set_style(progress_bar_style);
             progress_bar.set_message(format!(
@@ -202,16 +375,30 @@ async fn download_files(
                 path.file_name().unwrap_or_else(|| OsStr::new("unknown"))
             ));
 
-            let mut file = tokio::fs::File::create(&path).await?;
-            let mut stream = resp.bytes_stream();
-
-            while let Some(chunk_result) = stream.next().await {
-                let chunk = chunk_result?;
-                file.write_all(&chunk).await?;
-                progress_bar.inc(chunk.len() as u64);
+            match content_range {
+                Some((first_end, total)) => {
+                    match download_chunked(
+                        &client,
+                        &url,
+                        &path,
+                        resp,
+                        first_end,
+                        total,
+                        &progress_bar,
+                    )
+                    .await
+                    {
+                        Err(Error::RangeRequestIgnored) => {
+                            progress_bar.reset();
+                            let resp = client.get(&url).send().await?.error_for_status()?;
+                            download_whole(resp, &path, &progress_bar).await?;
+                        }
+                        result => result?,
+                    }
+                }
+                None => download_whole(resp, &path, &progress_bar).await?,
             }
 
-            file.flush().await?;
             progress_bar.finish_and_clear();
 
             main_pb.inc(1);
@@ -222,7 +409,8 @@ async fn download_files(
     join_all(handles)
         .await
         .into_iter()
-        .collect::<Result<Vec<_>, _>>()?;
+        .map(|result| result?)
+        .collect::<TResult<Vec<_>>>()?;
 
     main_pb.finish_and_clear();
     println!("Finished downloading files");
@@ -230,9 +418,110 @@ async fn download_files(
     Ok(())
 }
 
+async fn download_whole(resp: Response, path: &Path, progress_bar: &ProgressBar) -> TResult<()> {
+    let mut file = tokio::fs::File::create(path).await?;
+    let mut stream = resp.bytes_stream();
+
+    while let Some(chunk_result) = stream.next().await {
+        let chunk = chunk_result?;
+        file.write_all(&chunk).await?;
+        progress_bar.inc(chunk.len() as u64);
+    }
+
+    file.flush().await?;
+    Ok(())
+}
+
+/// Reads `bytes <start>-<end>/<total>` into its end and total
+fn parse_content_range(resp: &Response) -> Option<(u64, u64)> {
+    let content_range = resp.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    let (range, total) = content_range.strip_prefix("bytes ")?.split_once('/')?;
+    let (_, end) = range.split_once('-')?;
+
+    Some((end.parse().ok()?, total.parse().ok()?))
+}
+
+// the rest of the file after the first range gets split up, big files into a few byte ranges
+// which are downloaded at the same time and written straight into their place in the file.
+async fn download_chunked(
+    client: &ClientWithMiddleware,
+    url: &str,
+    path: &Path,
+    first_range: Response,
+    first_end: u64,
+    content_size: u64,
+    progress_bar: &ProgressBar,
+) -> TResult<()> {
+    let file = tokio::fs::File::create(path).await?;
+    file.set_len(content_size).await?;
+    drop(file);
+
+    let rest_start = first_end + 1;
+    let chunk_count = if content_size >= CHUNKED_DOWNLOAD_THRESHOLD {
+        DOWNLOAD_CHUNK_COUNT
+    } else {
+        1
+    };
+    let chunk_size = content_size
+        .saturating_sub(rest_start)
+        .div_ceil(chunk_count)
+        .max(1);
+    let chunks = (0..chunk_count)
+        .map(|i| rest_start + i * chunk_size)
+        .take_while(|start| *start < content_size)
+        .map(|start| {
+            let end = (start + chunk_size).min(content_size) - 1;
+
+            async move {
+                let resp = client
+                    .get(url)
+                    .header(RANGE, format!("bytes={start}-{end}"))
+                    .header(ACCEPT_ENCODING, "identity")
+                    .send()
+                    .await?;
+
+                if resp.status() != StatusCode::PARTIAL_CONTENT {
+                    return Err(Error::RangeRequestIgnored);
+                }
+
+                write_range(resp, path, start, progress_bar).await
+            }
+        });
+
+    try_join(
+        write_range(first_range, path, 0, progress_bar),
+        try_join_all(chunks),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn write_range(
+    resp: Response,
+    path: &Path,
+    start: u64,
+    progress_bar: &ProgressBar,
+) -> TResult<()> {
+    let mut file = OpenOptions::new().write(true).open(path).await?;
+    file.seek(SeekFrom::Start(start)).await?;
+    let mut stream = resp.bytes_stream();
+
+    while let Some(chunk_result) = stream.next().await {
+        let chunk = chunk_result?;
+        file.write_all(&chunk).await?;
+        progress_bar.inc(chunk.len() as u64);
+    }
+
+    file.flush().await?;
+    Ok(())
+}
+
 // gotta love working on an async environment.
 // the need of having everything touching async be async or else we would block everything like a brick wall.
-async fn check_file_hash(content_file: &ContentFile, file_path: &PathBuf) -> TResult<bool> {
+pub(super) async fn check_file_hash(
+    content_file: &ContentFile,
+    file_path: &PathBuf,
+) -> TResult<bool> {
     if !file_path.exists() {
         return Ok(false);
     }
@@ -261,7 +550,7 @@ async fn check_file_hash(content_file: &ContentFile, file_path: &PathBuf) -> TRe
 
 async fn verify_and_download(
     client: &ClientWithMiddleware,
-    content_files: &Vec<ContentFile>,
+    content_files: &[ContentFile],
     content_dir: PathBuf,
     download_first: bool,
     no_verify: bool,
@@ -285,12 +574,12 @@ async fn verify_and_download(
     }
 
     progress_bar.set_draw_target(ProgressDrawTarget::stderr());
-    loop {
+    for attempt in 1.. {
         progress_bar.reset();
 
         let failed_files = Arc::new(tokio::sync::Mutex::new(Vec::new()));
 
-        let handles = content_files.into_iter().map(|file| {
+        let handles = content_files.iter().map(|file| {
             let progress_bar = progress_bar.clone();
             let failed_files = failed_files.clone();
             let concurrency = concurrency.clone();
@@ -312,11 +601,15 @@ async fn verify_and_download(
         join_all(handles)
             .await
             .into_iter()
-            .collect::<Result<Vec<_>, _>>()?;
+            .map(|result| result?)
+            .collect::<TResult<Vec<_>>>()?;
 
         progress_bar.finish_and_clear();
 
         let mut failed_files = failed_files.lock().await;
+        if !failed_files.is_empty() && attempt > MAX_DOWNLOAD_ATTEMPTS {
+            return Err(Error::VerificationFailed(failed_files.len()));
+        }
         if !failed_files.is_empty() {
             println!(
                 "Failed to verify {} file{}. Retrying download...",
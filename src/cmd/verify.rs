@@ -0,0 +1,157 @@
+use std::{path::Path, sync::Arc};
+
+use color_print::ceprintln;
+use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use futures_util::future::join_all;
+use tokio::{fs, sync::Semaphore};
+
+use crate::{
+    cmd::{
+        Run, Verify,
+        update::{
+            ContentFile, ContentFiles, build_reqwest_client, check_file_hash,
+            get_cached_manifest_path, get_content_dir, get_content_files, parse_content_files,
+        },
+    },
+    errors::{Error, TResult},
+    game::{Game, pick_game},
+};
+
+impl Run for Verify {
+    async fn run(&self) -> TResult<()> {
+        // only the content dir gets looked at, so there's no need to find the game itself
+        let game = match self.game {
+            Some(game) => game,
+            None => pick_game(None, None)?.0,
+        };
+
+        let files = get_manifest().await?.for_game(game);
+        let content_dir = get_content_dir()?;
+
+        let report = VerifyReport {
+            game,
+            files: verify_files(&files, &content_dir).await?,
+        };
+        let failed = report
+            .files
+            .iter()
+            .filter(|f| f.status != FileStatus::Ok)
+            .count();
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .apply_modifier(UTF8_ROUND_CORNERS)
+                .set_header(vec!["File", "Status"]);
+
+            for file in &report.files {
+                table.add_row(vec![
+                    Cell::new(&file.path),
+                    Cell::new(file.status.to_str()).fg(match file.status {
+                        FileStatus::Ok => Color::Green,
+                        FileStatus::Missing => Color::Yellow,
+                        FileStatus::Mismatched => Color::Red,
+                    }),
+                ]);
+            }
+
+            println!("{table}");
+            println!(
+                "{} of {} {:?} mod files are OK",
+                report.files.len() - failed,
+                report.files.len(),
+                game
+            );
+        }
+
+        if failed > 0 {
+            return Err(Error::VerificationFailed(failed));
+        }
+
+        Ok(())
+    }
+}
+
+// an offline machine can still be checked against the files.json from the last update
+async fn get_manifest() -> TResult<ContentFiles> {
+    let reqwest_client = build_reqwest_client(3)?;
+    let error = match get_content_files(&reqwest_client).await {
+        Ok(files) => return Ok(files),
+        Err(e) => e,
+    };
+
+    let Ok(manifest) = fs::read_to_string(get_cached_manifest_path()?).await else {
+        return Err(error);
+    };
+    // stderr so it doesn't end up in the --json output
+    ceprintln!(
+        "<yellow,bold>warning</>: couldn't fetch the latest files.json, verifying against the cached one: {error}"
+    );
+
+    parse_content_files(&manifest)
+}
+
+#[derive(Debug, serde::Serialize)]
+struct VerifyReport {
+    game: Game,
+    files: Vec<FileReport>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub(super) struct FileReport {
+    pub(super) path: String,
+    pub(super) status: FileStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum FileStatus {
+    Ok,
+    Missing,
+    Mismatched,
+}
+
+impl FileStatus {
+    pub(super) fn to_str(self) -> &'static str {
+        match self {
+            FileStatus::Ok => "OK",
+            FileStatus::Missing => "Missing",
+            FileStatus::Mismatched => "Mismatched",
+        }
+    }
+}
+
+/// Checks every file against its manifest hash without touching the network
+pub(super) async fn verify_files(
+    content_files: &[ContentFile],
+    content_dir: &Path,
+) -> TResult<Vec<FileReport>> {
+    let concurrency = Arc::new(Semaphore::new(8));
+
+    let handles = content_files.iter().map(|file| {
+        let concurrency = concurrency.clone();
+        let file = file.clone();
+        let path = content_dir.join(&file.file_path);
+        tokio::spawn(async move {
+            let _ticket = concurrency.acquire().await?;
+
+            let status = if !path.exists() {
+                FileStatus::Missing
+            } else if check_file_hash(&file, &path).await? {
+                FileStatus::Ok
+            } else {
+                FileStatus::Mismatched
+            };
+
+            Ok::<FileReport, Error>(FileReport {
+                path: file.file_path,
+                status,
+            })
+        })
+    });
+
+    join_all(handles).await.into_iter().map(|r| r?).collect()
+}
@@ -0,0 +1,163 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use clap::crate_name;
+use dirs::config_dir;
+use once_cell::sync::OnceCell;
+
+use crate::{
+    errors::{Error, TResult},
+    game::Game,
+};
+
+static CONFIG: OnceCell<Config> = OnceCell::new();
+
+/// The user's `config.toml`. Every section is optional so an empty or missing file is fine.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub network: NetworkConfig,
+    pub api: ApiConfig,
+    pub content: ContentConfig,
+    pub games: GamesConfig,
+    pub profiles: HashMap<String, ProfileConfig>,
+    pub proton: ProtonConfig,
+    pub hooks: HooksConfig,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// An http://, https:// or socks5:// proxy every request goes through.
+    /// The usual HTTP_PROXY and HTTPS_PROXY env vars are still honored when unset.
+    pub proxy: Option<String>,
+    /// Extra PEM encoded CA certificates to trust, for those TLS inspecting proxies
+    pub ca_certificates: Vec<PathBuf>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// Where the TruckersMP API lives, defaults to https://api.truckersmp.com/v2/
+    pub base_url: Option<String>,
+    /// Request timeout in seconds
+    pub timeout: u64,
+    pub retries: u32,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            base_url: None,
+            timeout: 10,
+            retries: 3,
+        }
+    }
+}
+
+/// Where the mod files come from
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ContentConfig {
+    /// A `content serve` mirror like `http://192.168.1.10:8080` to update from instead of
+    /// TruckersMP's own servers
+    pub mirror: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct GamesConfig {
+    /// The game to go with when both are installed and none was given
+    pub default: Option<Game>,
+    pub ets2: GameConfig,
+    pub ats: GameConfig,
+}
+
+impl GamesConfig {
+    pub fn get(&self, game: Game) -> &GameConfig {
+        match game {
+            Game::ETS2 => &self.ets2,
+            Game::ATS => &self.ats,
+        }
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    /// Arguments always passed to the game, like `-nointro`
+    pub args: Vec<String>,
+    /// The game's install folder (the one with `bin` in it), for installs Steam doesn't know
+    /// about or gets wrong. Skips the Steam checks entirely
+    pub path: Option<PathBuf>,
+    /// Extra dlls loaded into the game after TruckersMP, like overlays or telemetry plugins
+    pub inject: Vec<PathBuf>,
+}
+
+/// Shell commands run around updating and playing. Unknown names are an error, a typo'd
+/// `pre_launch` silently not running would be worse
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    pub pre_update: Option<String>,
+    pub post_update: Option<String>,
+    /// Exiting non-zero cancels the launch
+    pub pre_launch: Option<String>,
+    pub post_launch: Option<String>,
+    /// Makes `run` wait for the game to exit
+    pub post_exit: Option<String>,
+}
+
+/// Launching through Proton on Linux
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ProtonConfig {
+    /// The Proton install to use, instead of the one the game's prefix was made with
+    pub path: Option<PathBuf>,
+    /// The Windows build of truckersmp-cli that gets run inside the prefix,
+    /// defaults to `truckersmp-cli.exe` next to the mod files
+    pub cli: Option<PathBuf>,
+}
+
+/// A named launch setup, used with `run --profile <name>`
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ProfileConfig {
+    /// The game to launch, the usual detection is used when unset
+    pub game: Option<Game>,
+    /// Arguments passed to the game after the per game ones
+    pub args: Vec<String>,
+    /// Extra dlls injected after the per game ones
+    pub inject: Vec<PathBuf>,
+    /// Environment variables set for the game process
+    pub env: HashMap<String, String>,
+    /// Passed to the game as `-homedir` so the profile gets its own documents folder
+    pub homedir: Option<PathBuf>,
+    /// Whether to verify the mod files before launching, defaults to true
+    pub verify: Option<bool>,
+}
+
+pub fn get_config_path() -> TResult<PathBuf> {
+    Ok(config_dir()
+        .ok_or_else(|| Error::NoAppdataPath)?
+        .join(crate_name!())
+        .join("config.toml"))
+}
+
+pub fn get_config() -> TResult<&'static Config> {
+    CONFIG.get_or_try_init(|| {
+        let path = get_config_path()?;
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let config = std::fs::read_to_string(&path)?;
+        toml::from_str(&config).map_err(|e| Error::InvalidConfig(path, e))
+    })
+}
+
+pub fn get_profile(name: &str) -> TResult<&'static ProfileConfig> {
+    get_config()?
+        .profiles
+        .get(name)
+        .ok_or_else(|| Error::UnknownProfile(name.to_string()))
+}
@@ -1,11 +1,12 @@
 use steamworks::{SteamAPIInitError, SteamError};
 
-use crate::game::Game;
+use crate::game::{Game, GameVersion};
 
 pub type TResult<T> = std::result::Result<T, Error>;
 
 // i love my error messages.
 #[derive(Debug, thiserror::Error)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     #[error("Steam is not running. Pretty please start Steam and try again!")]
     SteamNotRunning,
@@ -27,8 +28,92 @@ pub enum Error {
     GamesNotOwned,
     #[error("Sadly, we failed to launch game process in a suspended state")]
     FailedGameLaunch,
-    #[error("Crap! DLL injection has failed: {0}")]
-    FailedInjectingDLL(String),
+    #[cfg(target_os = "linux")]
+    #[error(
+        "{0:?} has no Proton prefix yet. Launch it from Steam once with Proton enabled so it gets one"
+    )]
+    NoProtonPrefix(Game),
+    #[cfg(target_os = "linux")]
+    #[error("Couldn't figure out which Proton {0:?} uses. Set `path` under [proton] in the config")]
+    UnknownProton(Game),
+    #[cfg(target_os = "linux")]
+    #[error(
+        "Launching through Proton needs the Windows build of truckersmp-cli at {0:?}. Grab it from the releases or set `cli` under [proton] in the config"
+    )]
+    NoWindowsCli(std::path::PathBuf),
+    #[cfg(target_os = "linux")]
+    #[error(
+        "The Windows build of truckersmp-cli needs steam_api64.dll next to it, but there's none at {0:?}. It comes with the release"
+    )]
+    NoSteamApiDll(std::path::PathBuf),
+    #[cfg(target_os = "linux")]
+    #[error(
+        "The Windows build of truckersmp-cli exited ({1}) before {0:?} showed up. Check Proton's output above for what went wrong"
+    )]
+    ProtonHelperExited(Game, std::process::ExitStatus),
+    #[cfg(not(any(windows, target_os = "linux")))]
+    #[error("Launching the game isn't supported on this OS yet")]
+    UnsupportedPlatform,
+    #[cfg(not(windows))]
+    #[error("The inject command only works on Windows, or inside Proton")]
+    InjectNotSupported,
+    #[error("Couldn't find {0:?}, you might want to update the mod files")]
+    DllNotFound(std::path::PathBuf),
+    #[error("{0:?} is already running. Close it first or use --replace to kill it")]
+    GameAlreadyRunning(Game),
+    #[error("Couldn't kill the running {0:?}, you'll have to close it yourself")]
+    FailedKillingGame(Game),
+    #[error("Couldn't find the dll {0:?} that was asked to be injected")]
+    ExtraDllNotFound(std::path::PathBuf),
+    #[error("{0:?} isn't a 64-bit dll, the game can't load it")]
+    ExtraDllNot64Bit(std::path::PathBuf),
+    #[cfg(windows)]
+    #[error("Crap! DLL injection has failed: couldn't allocate memory in the game process")]
+    InjectionAllocFailed,
+    #[cfg(windows)]
+    #[error("Crap! DLL injection has failed: couldn't write to the game process memory")]
+    InjectionWriteFailed,
+    #[cfg(windows)]
+    #[error("Crap! DLL injection has failed: couldn't find LoadLibraryW in kernel32.dll")]
+    LoadLibraryNotFound,
+    #[cfg(windows)]
+    #[error("Crap! DLL injection has failed: couldn't create a thread in the game process")]
+    RemoteThreadFailed,
+    #[cfg(windows)]
+    #[error("Crap! DLL injection has failed: {0:?} took too long to load")]
+    InjectionTimedOut(std::path::PathBuf),
+    #[cfg(windows)]
+    #[error("Crap! DLL injection has failed: couldn't tell whether the dll got loaded")]
+    InjectionWaitFailed,
+    #[cfg(windows)]
+    #[error(
+        "Crap! DLL injection has failed: the game couldn't load {0:?}. Make sure it's a 64-bit dll and its dependencies are around"
+    )]
+    DllLoadFailed(std::path::PathBuf),
+    #[error(
+        "{0:?} {1} is newer than what TruckersMP supports ({2}). Switch to the `{3}` beta branch in Steam (Properties > Betas) until the mod catches up, or use --force to launch anyway"
+    )]
+    GameTooNew(Game, GameVersion, GameVersion, String),
+    #[error(
+        "{0:?} {1} is older than what TruckersMP supports ({2}). Update the game through Steam or use --force to launch anyway"
+    )]
+    GameTooOld(Game, GameVersion, GameVersion),
+    #[error(
+        "{0:?} {1} is on the `{2}` beta branch but TruckersMP supports {3}. Switch to the `{4}` beta branch or back to the default one in Steam (Properties > Betas), or use --force to launch anyway"
+    )]
+    GameOnStaleBranch(Game, GameVersion, String, GameVersion, String),
+    #[error("Couldn't find where Steam is installed")]
+    SteamNotFound,
+    #[error("Couldn't find the Steam app manifest of {0:?}")]
+    NoAppManifest(Game),
+    #[error("Couldn't make sense of Steam's {0:?}")]
+    InvalidVdf(std::path::PathBuf),
+    #[error("{0:?} doesn't look like a Windows executable")]
+    InvalidExecutable(std::path::PathBuf),
+    #[error("Couldn't tell how the game exited")]
+    UnknownGameExitCode,
+    #[error("{0:?} exited with code {1:#x}, looks like it crashed")]
+    GameCrashed(Game, u32),
     #[error("Our reqwest client has gone crazy and failed with: {0}")]
     ReqwestMiddlewareClientError(#[from] reqwest_middleware::Error),
     #[error("Our reqwest client has gone crazy and failed with: {0}")]
@@ -41,10 +126,43 @@ pub enum Error {
     TokioJoinError(#[from] tokio::task::JoinError),
     #[error("Somehow we couldn't find your roaming AppData folder... how?")]
     NoAppdataPath,
-    #[error("Somehow we couldn't find the {0:?} executable in its directory")]
+    #[error("Your config at {0:?} doesn't look right: {1}")]
+    InvalidConfig(std::path::PathBuf, toml::de::Error),
+    #[error("There's no profile called {0:?} in your config")]
+    UnknownProfile(String),
+    #[error("The {0} hook failed with exit code {1:?}")]
+    HookFailed(&'static str, Option<i32>),
+    #[error(
+        "Somehow we couldn't find the {0:?} executable in its directory. If the game lives somewhere else, point --game-path or `path` under [games.{game}] in the config at it",
+        game = format!("{:?}", .0).to_lowercase()
+    )]
     GameExecutableNotFound(Game),
-    #[error("Sadly the TruckersMP API errored out while trying to fetch data from it")]
-    TruckersMPError,
+    #[error("{1:?} doesn't look like a {0:?} install as there's no bin/win_x64/{exe} in it", exe = .0.exe())]
+    InvalidGamePath(Game, std::path::PathBuf),
+    #[error("No game was picked. Pass --game to choose one")]
+    NoGameChosen,
+    #[error("Neither ETS2 nor ATS seem to be installed in {0:?}")]
+    UnknownGamePath(std::path::PathBuf),
+    #[error("The download server ignored our range request")]
+    RangeRequestIgnored,
+    #[error("Sadly the TruckersMP API errored out: {0}")]
+    TruckersMPError(String),
+    #[error("{0} mod file(s) failed verification. Running the update command should fix them")]
+    VerificationFailed(usize),
+    #[error("Couldn't make sense of some JSON: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Couldn't read the zip archive: {0}")]
+    ZipError(#[from] zip::result::ZipError),
+    #[error("{0:?} is neither a directory nor a zip or tar archive")]
+    UnsupportedBundle(std::path::PathBuf),
+    #[error(
+        "There is no files.json in the bundle and none cached either. Run the update command once while online"
+    )]
+    NoContentManifest,
+    #[error("{0} mod file(s) are missing or corrupted in the bundle")]
+    IncompleteBundle(usize),
+    #[error("There's nothing to export as no game has all of its mod files verified")]
+    NothingToExport,
 }
 
 impl From<SteamAPIInitError> for Error {
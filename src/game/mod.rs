@@ -0,0 +1,411 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use color_print::cprintln;
+use steamworks::AppId;
+
+use crate::{
+    config::get_config,
+    errors::{Error, TResult},
+    game::{
+        picker::choose_game,
+        source::{ManualSource, OverrideSource, get_steam_source},
+    },
+    pe,
+};
+
+pub mod picker;
+pub mod source;
+
+pub use source::GameSource;
+
+#[derive(
+    PartialEq, Eq, Hash, Clone, Copy, Debug, ValueEnum, serde::Deserialize, serde::Serialize,
+)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Game {
+    #[serde(alias = "ets2")]
+    ETS2 = 227300,
+    #[serde(alias = "ats")]
+    ATS = 270880,
+}
+
+impl From<Game> for AppId {
+    fn from(value: Game) -> Self {
+        AppId(value as u32)
+    }
+}
+
+impl Game {
+    pub fn exe(&self) -> &'static str {
+        match self {
+            Game::ETS2 => "eurotrucks2.exe",
+            Game::ATS => "amtrucks.exe",
+        }
+    }
+
+    pub fn dll(&self) -> &'static str {
+        match self {
+            Game::ETS2 => "core_ets2mp.dll",
+            Game::ATS => "core_atsmp.dll",
+        }
+    }
+}
+
+/// A game version like `1.53.3.14`
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub struct GameVersion(pub [u16; 4]);
+
+impl GameVersion {
+    /// Parses versions the way the TruckersMP API hands them out, suffix and all (`1.53.3.14s`)
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = [0; 4];
+        let mut count = 0;
+        for (part, component) in parts.iter_mut().zip(version.split('.')) {
+            let digits = component
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(component, |end| &component[..end]);
+            *part = digits.parse().ok()?;
+            count += 1;
+        }
+
+        // anything less than major.minor is not a version
+        (count >= 2).then_some(GameVersion(parts))
+    }
+
+    /// The mod only cares about major.minor, patches within a release don't break it
+    pub fn same_release(&self, other: &GameVersion) -> bool {
+        self.0[..2] == other.0[..2]
+    }
+
+    /// SCS keeps the previous releases around as `temporary_1_xx` beta branches on Steam
+    pub fn beta_branch(&self) -> String {
+        format!("temporary_{}_{}", self.0[0], self.0[1])
+    }
+}
+
+impl fmt::Display for GameVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [major, minor, patch, build] = self.0;
+        write!(f, "{major}.{minor}.{patch}.{build}")
+    }
+}
+
+/// Reads the installed game's version straight from its executable
+pub fn get_game_version(game_path: &Path) -> TResult<Option<GameVersion>> {
+    Ok(pe::read_file_version(game_path)?.map(GameVersion))
+}
+
+/// Picks the game to work with, sticking to `game` if one was asked for. Install folders from
+/// the config or `--game-path` take priority over Steam, and also decide the game when needed.
+/// With both games installed the choice is left to [`choose_game`].
+///
+/// Whatever is installed is good enough here, see [`pick_game_to_launch`] for when ownership
+/// matters too.
+pub fn pick_game(
+    game: Option<Game>,
+    game_path: Option<&Path>,
+) -> TResult<(Game, Box<dyn GameSource>)> {
+    pick(game, game_path, false)
+}
+
+/// Like [`pick_game`], but also asks Steam whether the game is owned. That means starting the
+/// Steam API, which is only worth it right before launching
+pub fn pick_game_to_launch(
+    game: Option<Game>,
+    game_path: Option<&Path>,
+) -> TResult<(Game, Box<dyn GameSource>)> {
+    pick(game, game_path, true)
+}
+
+fn pick(
+    game: Option<Game>,
+    game_path: Option<&Path>,
+    check_ownership: bool,
+) -> TResult<(Game, Box<dyn GameSource>)> {
+    let mut overrides = ManualSource::default();
+    for game in [Game::ETS2, Game::ATS] {
+        if let Some(path) = &get_config()?.games.get(game).path {
+            overrides = overrides.with(game, path.clone());
+        }
+    }
+
+    let game = match (game, game_path) {
+        (Some(game), _) => Some(game),
+        (None, Some(path)) => Some(
+            [Game::ETS2, Game::ATS]
+                .into_iter()
+                .find(|game| has_game_layout(*game, path))
+                .ok_or_else(|| Error::UnknownGamePath(path.to_path_buf()))?,
+        ),
+        (None, None) => None,
+    };
+    if let (Some(game), Some(path)) = (game, game_path) {
+        overrides = overrides.with(game, path.to_path_buf());
+    }
+
+    // only the folders this command could end up using have to be right
+    for (overridden, path) in overrides.iter() {
+        if game.is_some_and(|game| game != overridden) {
+            continue;
+        }
+
+        if !has_game_layout(overridden, path) {
+            return Err(Error::InvalidGamePath(overridden, path.clone()));
+        }
+        cprintln!(
+            "<yellow,bold>warning</>: using {:?} from {}, Steam won't be asked whether you own or installed it",
+            overridden,
+            path.display()
+        );
+    }
+
+    let source: Box<dyn GameSource> = if overrides.is_empty() {
+        get_steam_source(check_ownership)?
+    } else {
+        Box::new(OverrideSource::new(overrides, check_ownership))
+    };
+
+    let game = match game {
+        Some(game) => get_specific_game(&*source, game)?,
+        None => match get_games(&*source)? {
+            AvailableGames {
+                ets2: true,
+                ats: true,
+            } => choose_game()?,
+            _ => get_available_games(&*source)?,
+        },
+    };
+
+    Ok((game, source))
+}
+
+fn has_game_layout(game: Game, install_dir: &Path) -> bool {
+    install_dir
+        .join("bin")
+        .join("win_x64")
+        .join(game.exe())
+        .is_file()
+}
+
+#[derive(Default)]
+struct AvailableGames {
+    ets2: bool,
+    ats: bool,
+}
+
+pub fn get_available_games(source: &dyn GameSource) -> TResult<Game> {
+    let available_games = get_games(source)?;
+
+    match available_games {
+        AvailableGames { ets2: true, .. } => Ok(Game::ETS2),
+        AvailableGames { ats: true, .. } => Ok(Game::ATS),
+        _ => Err(Error::GamesNotInstalled),
+    }
+}
+
+// a game is only treated as not owned when that's known for sure, otherwise the files get
+// the benefit of the doubt
+fn may_own(source: &dyn GameSource, game: Game) -> bool {
+    source.is_owned(game) != Some(false)
+}
+
+pub fn get_specific_game(source: &dyn GameSource, game: Game) -> TResult<Game> {
+    if may_own(source, game) {
+        if !source.is_installed(game) {
+            println!("{:?} is not installed", game);
+            return Err(Error::SpecificGameNotInstalled(game));
+        }
+
+        return Ok(game);
+    }
+    Err(Error::SpecificGameNotOwned(game))
+}
+
+fn get_games(source: &dyn GameSource) -> TResult<AvailableGames> {
+    let mut result = AvailableGames::default();
+    let mut owned_not_installed = 0;
+    let mut owned = 0;
+    if may_own(source, Game::ETS2) {
+        owned += 1;
+        if source.is_installed(Game::ETS2) {
+            result.ets2 = true;
+        } else {
+            owned_not_installed += 1;
+        }
+    }
+    if may_own(source, Game::ATS) {
+        owned += 1;
+        if source.is_installed(Game::ATS) {
+            result.ats = true;
+        } else {
+            owned_not_installed += 1;
+        }
+    }
+
+    if owned_not_installed == 2 {
+        return Err(Error::GamesNotInstalled);
+    }
+
+    if owned == 0 {
+        return Err(Error::GamesNotOwned);
+    }
+
+    Ok(result)
+}
+
+pub fn get_game_path(source: &dyn GameSource, game: Game) -> TResult<PathBuf> {
+    let game_dir = source
+        .install_dir(game)
+        .ok_or(Error::SpecificGameNotInstalled(game))?
+        .join("bin")
+        .join("win_x64")
+        .join(game.exe());
+
+    if !game_dir.exists() {
+        return Err(Error::GameExecutableNotFound(game));
+    }
+
+    Ok(game_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        source::fake::{FakeSource, FakeState},
+        *,
+    };
+
+    const STATES: [FakeState; 3] = [FakeState::NotOwned, FakeState::Owned, FakeState::Installed];
+
+    #[test]
+    fn picks_available_game_for_every_combination() {
+        use FakeState::*;
+
+        for ets2 in STATES {
+            for ats in STATES {
+                let result = get_available_games(&FakeSource::new(ets2, ats));
+
+                match (ets2, ats) {
+                    // ETS2 wins whenever both are there
+                    (Installed, _) => assert!(matches!(result, Ok(Game::ETS2))),
+                    (_, Installed) => assert!(matches!(result, Ok(Game::ATS))),
+                    (NotOwned, NotOwned) => assert!(matches!(result, Err(Error::GamesNotOwned))),
+                    _ => assert!(
+                        matches!(result, Err(Error::GamesNotInstalled)),
+                        "{ets2:?} {ats:?} gave {result:?}"
+                    ),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn checks_specific_game_for_every_combination() {
+        for ets2 in STATES {
+            for ats in STATES {
+                let source = FakeSource::new(ets2, ats);
+
+                for (game, state) in [(Game::ETS2, ets2), (Game::ATS, ats)] {
+                    let result = get_specific_game(&source, game);
+                    match state {
+                        FakeState::Installed => assert!(matches!(result, Ok(g) if g == game)),
+                        FakeState::Owned => assert!(matches!(
+                            result,
+                            Err(Error::SpecificGameNotInstalled(g)) if g == game
+                        )),
+                        FakeState::NotOwned => assert!(matches!(
+                            result,
+                            Err(Error::SpecificGameNotOwned(g)) if g == game
+                        )),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn trusts_the_files_when_ownership_is_unknown() {
+        let mut source = FakeSource::new(FakeState::Installed, FakeState::NotOwned);
+        source.ownership_known = false;
+
+        assert!(matches!(get_available_games(&source), Ok(Game::ETS2)));
+        assert!(matches!(
+            get_specific_game(&source, Game::ETS2),
+            Ok(Game::ETS2)
+        ));
+        // nothing on disk is a missing install, not a missing purchase
+        assert!(matches!(
+            get_specific_game(&source, Game::ATS),
+            Err(Error::SpecificGameNotInstalled(Game::ATS))
+        ));
+
+        source.ets2 = FakeState::Owned;
+        assert!(matches!(
+            get_available_games(&source),
+            Err(Error::GamesNotInstalled)
+        ));
+    }
+
+    #[test]
+    fn finds_game_executable() {
+        let root =
+            std::env::temp_dir().join(format!("truckersmp-cli-test-game-{}", std::process::id()));
+        let mut source = FakeSource::new(FakeState::Installed, FakeState::Installed);
+        source.install_root = root.clone();
+
+        let exe_dir = root.join("ETS2").join("bin").join("win_x64");
+        std::fs::create_dir_all(&exe_dir).unwrap();
+        std::fs::write(exe_dir.join(Game::ETS2.exe()), b"").unwrap();
+
+        assert_eq!(
+            get_game_path(&source, Game::ETS2).unwrap(),
+            exe_dir.join(Game::ETS2.exe())
+        );
+        // installed according to steam, but the files aren't there
+        assert!(matches!(
+            get_game_path(&source, Game::ATS),
+            Err(Error::GameExecutableNotFound(Game::ATS))
+        ));
+
+        _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn manual_source_only_knows_what_it_was_given() {
+        let source = source::ManualSource::default().with(Game::ATS, PathBuf::from("ATS"));
+
+        assert!(matches!(get_available_games(&source), Ok(Game::ATS)));
+        assert!(matches!(
+            get_specific_game(&source, Game::ETS2),
+            Err(Error::SpecificGameNotOwned(Game::ETS2))
+        ));
+    }
+
+    #[test]
+    fn has_no_game_path_when_not_installed() {
+        let source = FakeSource::new(FakeState::Owned, FakeState::NotOwned);
+
+        assert!(matches!(
+            get_game_path(&source, Game::ETS2),
+            Err(Error::SpecificGameNotInstalled(Game::ETS2))
+        ));
+    }
+
+    #[test]
+    fn parses_api_game_versions() {
+        let supported = GameVersion::parse("1.53.3.14s").unwrap();
+        assert_eq!(supported, GameVersion([1, 53, 3, 14]));
+        assert_eq!(supported.beta_branch(), "temporary_1_53");
+
+        assert!(supported.same_release(&GameVersion([1, 53, 4, 2])));
+        assert!(!supported.same_release(&GameVersion([1, 54, 0, 1])));
+        assert!(GameVersion([1, 54, 0, 1]) > supported);
+
+        assert_eq!(GameVersion::parse("1.50"), Some(GameVersion([1, 50, 0, 0])));
+        assert_eq!(GameVersion::parse("soon"), None);
+    }
+}
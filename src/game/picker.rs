@@ -0,0 +1,65 @@
+use std::io::{self, IsTerminal as _, Write as _};
+
+use crate::{
+    config::get_config,
+    errors::{Error, TResult},
+    game::Game,
+    state::{read_state, update_state},
+};
+
+/// For when both games are installed and nobody said which one. Goes with the configured
+/// default, then the remembered choice, then asks if there's anyone to ask, and otherwise
+/// falls back to the last played game or ETS2.
+pub fn choose_game() -> TResult<Game> {
+    if let Some(game) = get_config()?.games.default {
+        return Ok(game);
+    }
+
+    let state = read_state();
+    if let Some(game) = state.default_game {
+        return Ok(game);
+    }
+
+    if io::stdin().is_terminal() && io::stdout().is_terminal() {
+        return prompt_game(state.last_game);
+    }
+
+    Ok(state.last_game.unwrap_or(Game::ETS2))
+}
+
+fn prompt_game(last_game: Option<Game>) -> TResult<Game> {
+    let hint = last_game
+        .map(|game| format!(" (enter for {game:?})"))
+        .unwrap_or_default();
+
+    let game = loop {
+        print!("Both ETS2 and ATS are installed, which one? [1] ETS2 [2] ATS{hint}: ");
+        io::stdout().flush()?;
+
+        match (read_answer()?.as_str(), last_game) {
+            ("1" | "ets2", _) => break Game::ETS2,
+            ("2" | "ats", _) => break Game::ATS,
+            ("", Some(game)) => break game,
+            _ => continue,
+        }
+    };
+
+    print!("Remember this choice? [y/N]: ");
+    io::stdout().flush()?;
+    if matches!(read_answer()?.as_str(), "y" | "yes") {
+        update_state(|state| state.default_game = Some(game))?;
+        println!("Got it! Pass --game to play the other one");
+    }
+
+    Ok(game)
+}
+
+fn read_answer() -> TResult<String> {
+    let mut answer = String::new();
+    // ctrl+d and friends
+    if io::stdin().read_line(&mut answer)? == 0 {
+        return Err(Error::NoGameChosen);
+    }
+
+    Ok(answer.trim().to_lowercase())
+}
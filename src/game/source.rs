@@ -0,0 +1,253 @@
+//! Everywhere we can learn about the installed games from
+
+use std::{cell::OnceCell, collections::HashMap, path::PathBuf};
+
+use steamworks::{AppId, Client};
+
+use crate::{errors::TResult, game::Game, steam::library::SteamLibraries};
+
+/// Something that knows which games are owned, installed and where
+pub trait GameSource {
+    /// `None` when there's no telling, like when only Steam's files could be looked at
+    fn is_owned(&self, game: Game) -> Option<bool>;
+    fn is_installed(&self, game: Game) -> bool;
+    /// The game's root folder, the one with `bin` in it
+    fn install_dir(&self, game: Game) -> Option<PathBuf>;
+}
+
+/// Steam's own files, which is all we need most of the time. They can't tell whether a game
+/// is actually owned though, so that one question still goes to the Steam API when asked to
+pub struct LibrarySource {
+    libraries: SteamLibraries,
+    check_ownership: bool,
+    steamworks: OnceCell<Option<SteamworksSource>>,
+}
+
+impl LibrarySource {
+    pub fn new(libraries: SteamLibraries, check_ownership: bool) -> Self {
+        LibrarySource {
+            libraries,
+            check_ownership,
+            steamworks: OnceCell::new(),
+        }
+    }
+}
+
+impl GameSource for LibrarySource {
+    // only asked about games the files found, a missing one is reported as not installed and
+    // Steam stays out of it. no Steam running means no telling
+    fn is_owned(&self, game: Game) -> Option<bool> {
+        if !self.check_ownership {
+            return None;
+        }
+        self.libraries.find_game(game)?;
+        self.steamworks
+            .get_or_init(|| SteamworksSource::query().ok())
+            .as_ref()?
+            .is_owned(game)
+    }
+
+    fn is_installed(&self, game: Game) -> bool {
+        self.libraries
+            .find_game(game)
+            .is_some_and(|app| app.manifest.is_fully_installed())
+    }
+
+    fn install_dir(&self, game: Game) -> Option<PathBuf> {
+        Some(self.libraries.find_game(game)?.install_dir)
+    }
+}
+
+struct SteamworksApp {
+    owned: bool,
+    installed: bool,
+    install_dir: PathBuf,
+}
+
+/// What the Steam API told us, for ownership and for when Steam's files can't be found
+pub struct SteamworksSource {
+    apps: HashMap<Game, SteamworksApp>,
+}
+
+impl SteamworksSource {
+    // app id 480 is the safe bet as its the sdk demo app. it does make Steam show everyone that
+    // we're playing Spacewar though, so the client is shut down as soon as we got what we need
+    pub fn query() -> TResult<Self> {
+        let client = Client::init_app(AppId(480))?;
+        let apps = client.apps();
+
+        let apps = [Game::ETS2, Game::ATS]
+            .into_iter()
+            .map(|game| {
+                let app = SteamworksApp {
+                    owned: apps.is_subscribed_app(game.into()),
+                    installed: apps.is_app_installed(game.into()),
+                    install_dir: PathBuf::from(apps.app_install_dir(game.into())),
+                };
+                (game, app)
+            })
+            .collect();
+
+        // the client gets dropped right here, which shuts the Steam API down
+        Ok(SteamworksSource { apps })
+    }
+}
+
+impl GameSource for SteamworksSource {
+    fn is_owned(&self, game: Game) -> Option<bool> {
+        Some(self.apps.get(&game).is_some_and(|app| app.owned))
+    }
+
+    fn is_installed(&self, game: Game) -> bool {
+        self.apps.get(&game).is_some_and(|app| app.installed)
+    }
+
+    fn install_dir(&self, game: Game) -> Option<PathBuf> {
+        Some(self.apps.get(&game)?.install_dir.clone())
+    }
+}
+
+/// Game folders given to us by hand. Whatever is in here counts as owned and installed
+#[derive(Default)]
+pub struct ManualSource {
+    install_dirs: HashMap<Game, PathBuf>,
+}
+
+impl ManualSource {
+    pub fn with(mut self, game: Game, install_dir: PathBuf) -> Self {
+        self.install_dirs.insert(game, install_dir);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.install_dirs.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Game, &PathBuf)> {
+        self.install_dirs.iter().map(|(game, dir)| (*game, dir))
+    }
+}
+
+impl GameSource for ManualSource {
+    fn is_owned(&self, game: Game) -> Option<bool> {
+        Some(self.install_dirs.contains_key(&game))
+    }
+
+    fn is_installed(&self, game: Game) -> bool {
+        self.install_dirs.contains_key(&game)
+    }
+
+    fn install_dir(&self, game: Game) -> Option<PathBuf> {
+        self.install_dirs.get(&game).cloned()
+    }
+}
+
+/// Game folders set by hand win, Steam only gets asked about the others and only once it has to
+pub struct OverrideSource {
+    overrides: ManualSource,
+    check_ownership: bool,
+    steam: OnceCell<Option<Box<dyn GameSource>>>,
+}
+
+impl OverrideSource {
+    pub fn new(overrides: ManualSource, check_ownership: bool) -> Self {
+        OverrideSource {
+            overrides,
+            check_ownership,
+            steam: OnceCell::new(),
+        }
+    }
+
+    fn steam(&self) -> Option<&dyn GameSource> {
+        self.steam
+            .get_or_init(|| get_steam_source(self.check_ownership).ok())
+            .as_deref()
+    }
+}
+
+impl GameSource for OverrideSource {
+    fn is_owned(&self, game: Game) -> Option<bool> {
+        if self.overrides.install_dir(game).is_some() {
+            return Some(true);
+        }
+        self.steam()?.is_owned(game)
+    }
+
+    fn is_installed(&self, game: Game) -> bool {
+        self.overrides.is_installed(game)
+            || self.steam().is_some_and(|steam| steam.is_installed(game))
+    }
+
+    fn install_dir(&self, game: Game) -> Option<PathBuf> {
+        self.overrides
+            .install_dir(game)
+            .or_else(|| self.steam()?.install_dir(game))
+    }
+}
+
+/// Reads the Steam libraries off the disk, only asking the Steam API when Steam's files are
+/// nowhere to be found or when `check_ownership` needs it to
+pub fn get_steam_source(check_ownership: bool) -> TResult<Box<dyn GameSource>> {
+    match SteamLibraries::discover() {
+        Ok(libraries) => Ok(Box::new(LibrarySource::new(libraries, check_ownership))),
+        Err(_) => Ok(Box::new(SteamworksSource::query()?)),
+    }
+}
+
+#[cfg(test)]
+pub mod fake {
+    use std::path::PathBuf;
+
+    use super::GameSource;
+    use crate::game::Game;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum FakeState {
+        NotOwned,
+        Owned,
+        Installed,
+    }
+
+    /// Pretends to be Steam with whatever state a test wants
+    pub struct FakeSource {
+        pub ets2: FakeState,
+        pub ats: FakeState,
+        pub install_root: PathBuf,
+        /// Whether ownership can be told, like Steam's files without the Steam API
+        pub ownership_known: bool,
+    }
+
+    impl FakeSource {
+        pub fn new(ets2: FakeState, ats: FakeState) -> Self {
+            FakeSource {
+                ets2,
+                ats,
+                install_root: PathBuf::from("steamapps").join("common"),
+                ownership_known: true,
+            }
+        }
+
+        fn state(&self, game: Game) -> FakeState {
+            match game {
+                Game::ETS2 => self.ets2,
+                Game::ATS => self.ats,
+            }
+        }
+    }
+
+    impl GameSource for FakeSource {
+        fn is_owned(&self, game: Game) -> Option<bool> {
+            self.ownership_known
+                .then(|| self.state(game) != FakeState::NotOwned)
+        }
+
+        fn is_installed(&self, game: Game) -> bool {
+            self.state(game) == FakeState::Installed
+        }
+
+        fn install_dir(&self, game: Game) -> Option<PathBuf> {
+            self.is_installed(game)
+                .then(|| self.install_root.join(format!("{game:?}")))
+        }
+    }
+}
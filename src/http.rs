@@ -0,0 +1,23 @@
+use clap::crate_version;
+use reqwest::{Certificate, ClientBuilder, Proxy};
+
+use crate::{config::get_config, errors::TResult};
+
+/// Every reqwest client starts from here so the network settings from the config apply everywhere
+pub fn client_builder() -> TResult<ClientBuilder> {
+    let network = &get_config()?.network;
+    let mut builder = reqwest::Client::builder()
+        .user_agent(format!("Yet Another TruckersMP Cli/{:?}", crate_version!()));
+
+    if let Some(proxy) = &network.proxy {
+        builder = builder.proxy(Proxy::all(proxy)?);
+    }
+
+    for ca_certificate in &network.ca_certificates {
+        for certificate in Certificate::from_pem_bundle(&std::fs::read(ca_certificate)?)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    Ok(builder)
+}
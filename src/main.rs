@@ -5,13 +5,20 @@ use color_print::cwriteln;
 
 use crate::cmd::{Cmd, Run};
 
+mod api;
 mod cmd;
+mod config;
 mod errors;
 mod game;
+mod http;
+mod pe;
+mod state;
+mod steam;
 
 #[tokio::main]
 async fn main() -> ExitCode {
-    _ = nu_ansi_term::enable_ansi_support();
+    #[cfg(windows)]
+    let _ = nu_ansi_term::enable_ansi_support();
     match Cmd::parse().run().await {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
@@ -0,0 +1,169 @@
+//! Just enough PE parsing to read what we need out of the game's executables,
+//! without having to be on Windows to do so.
+
+use std::{
+    fs::File,
+    io::{Read as _, Seek as _, SeekFrom},
+    path::Path,
+};
+
+use crate::errors::{Error, TResult};
+
+const VS_FIXEDFILEINFO_SIGNATURE: u32 = 0xFEEF04BD;
+const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+
+struct Section {
+    name: [u8; 8],
+    raw_size: u32,
+    raw_offset: u32,
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        bytes.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+/// Returns the offset of the `PE\0\0` signature
+fn read_pe_offset(file: &mut File, path: &Path) -> TResult<u64> {
+    let mut dos_header = [0; 64];
+    file.read_exact(&mut dos_header)
+        .map_err(|_| Error::InvalidExecutable(path.to_path_buf()))?;
+
+    if &dos_header[..2] != b"MZ" {
+        return Err(Error::InvalidExecutable(path.to_path_buf()));
+    }
+    let pe_offset = read_u32(&dos_header, 0x3C).unwrap_or_default() as u64;
+
+    let mut signature = [0; 4];
+    file.seek(SeekFrom::Start(pe_offset))?;
+    file.read_exact(&mut signature)
+        .map_err(|_| Error::InvalidExecutable(path.to_path_buf()))?;
+    if &signature != b"PE\0\0" {
+        return Err(Error::InvalidExecutable(path.to_path_buf()));
+    }
+
+    Ok(pe_offset)
+}
+
+fn read_sections(file: &mut File, path: &Path) -> TResult<Vec<Section>> {
+    let pe_offset = read_pe_offset(file, path)?;
+
+    let mut coff_header = [0; 20];
+    file.read_exact(&mut coff_header)
+        .map_err(|_| Error::InvalidExecutable(path.to_path_buf()))?;
+    let section_count = read_u16(&coff_header, 2).unwrap_or_default() as usize;
+    let optional_header_size = read_u16(&coff_header, 16).unwrap_or_default() as u64;
+
+    let mut section_table = vec![0; section_count * 40];
+    file.seek(SeekFrom::Start(pe_offset + 24 + optional_header_size))?;
+    file.read_exact(&mut section_table)
+        .map_err(|_| Error::InvalidExecutable(path.to_path_buf()))?;
+
+    Ok(section_table
+        .chunks_exact(40)
+        .map(|section| Section {
+            name: section[..8].try_into().unwrap_or_default(),
+            raw_size: read_u32(section, 16).unwrap_or_default(),
+            raw_offset: read_u32(section, 20).unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Whether the executable or dll is built for x64, which is all the game can load
+pub fn is_64_bit(path: &Path) -> TResult<bool> {
+    let mut file = File::open(path)?;
+    read_pe_offset(&mut file, path)?;
+
+    // the machine type is the first thing in the COFF header, right after the signature
+    let mut machine = [0; 2];
+    file.read_exact(&mut machine)
+        .map_err(|_| Error::InvalidExecutable(path.to_path_buf()))?;
+
+    Ok(u16::from_le_bytes(machine) == IMAGE_FILE_MACHINE_AMD64)
+}
+
+/// Reads the file version (like `1.53.3.14`) from the executable's version resource
+pub fn read_file_version(path: &Path) -> TResult<Option<[u16; 4]>> {
+    let mut file = File::open(path)?;
+    let sections = read_sections(&mut file, path)?;
+
+    let Some(resources) = sections.iter().find(|s| s.name.starts_with(b".rsrc")) else {
+        return Ok(None);
+    };
+
+    let mut data = vec![0; resources.raw_size as usize];
+    file.seek(SeekFrom::Start(resources.raw_offset as u64))?;
+    file.read_exact(&mut data)
+        .map_err(|_| Error::InvalidExecutable(path.to_path_buf()))?;
+
+    // instead of walking the whole resource tree we look for VS_FIXEDFILEINFO's
+    // signature, which is always dword aligned
+    let version = (0..data.len().saturating_sub(16))
+        .step_by(4)
+        .find(|offset| read_u32(&data, *offset) == Some(VS_FIXEDFILEINFO_SIGNATURE))
+        .and_then(|offset| {
+            let most_significant = read_u32(&data, offset + 8)?;
+            let least_significant = read_u32(&data, offset + 12)?;
+
+            Some([
+                (most_significant >> 16) as u16,
+                most_significant as u16,
+                (least_significant >> 16) as u16,
+                least_significant as u16,
+            ])
+        });
+
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/pe")
+            .join(name)
+    }
+
+    #[test]
+    fn reads_file_version() {
+        let version = read_file_version(&fixture("version_x64.dll")).unwrap();
+        assert_eq!(version, Some([1, 53, 3, 14]));
+    }
+
+    #[test]
+    fn reads_no_version_without_resources() {
+        let version = read_file_version(&fixture("no_version_x86.dll")).unwrap();
+        assert_eq!(version, None);
+    }
+
+    #[test]
+    fn reads_machine_type() {
+        assert!(is_64_bit(&fixture("version_x64.dll")).unwrap());
+        assert!(!is_64_bit(&fixture("no_version_x86.dll")).unwrap());
+    }
+
+    #[test]
+    fn rejects_non_pe_files() {
+        let not_pe =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proton/config_info");
+        assert!(matches!(
+            read_file_version(&not_pe),
+            Err(Error::InvalidExecutable(_))
+        ));
+        assert!(matches!(
+            is_64_bit(&not_pe),
+            Err(Error::InvalidExecutable(_))
+        ));
+    }
+}
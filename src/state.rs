@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use clap::crate_name;
+use dirs::data_dir;
+
+use crate::{
+    errors::{Error, TResult},
+    game::Game,
+};
+
+/// Things we remember between runs, stored in `state.json` next to the mod files.
+/// Unlike the config this is ours to overwrite whenever.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    /// The game picked with "remember this choice" when both are installed
+    pub default_game: Option<Game>,
+    /// The game `run` launched last
+    pub last_game: Option<Game>,
+}
+
+fn get_state_path() -> TResult<PathBuf> {
+    Ok(data_dir()
+        .ok_or_else(|| Error::NoAppdataPath)?
+        .join(crate_name!())
+        .join("state.json"))
+}
+
+// a missing or mangled state file is no reason to stop anyone from playing
+pub fn read_state() -> State {
+    get_state_path()
+        .and_then(|path| Ok(std::fs::read_to_string(path)?))
+        .ok()
+        .and_then(|state| serde_json::from_str(&state).ok())
+        .unwrap_or_default()
+}
+
+pub fn update_state(update: impl FnOnce(&mut State)) -> TResult<()> {
+    let mut state = read_state();
+    update(&mut state);
+
+    let path = get_state_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(&state)?)?;
+
+    Ok(())
+}
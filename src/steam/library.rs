@@ -0,0 +1,261 @@
+//! Finds the installed games by going through every Steam library on disk, which works just
+//! fine without Steam running.
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    errors::{Error, TResult},
+    game::Game,
+    steam::{manifest::AppManifest, vdf::Vdf},
+};
+
+/// A game install found in one of the libraries
+#[derive(Debug, Clone)]
+pub struct InstalledApp {
+    pub manifest: AppManifest,
+    /// `<library>/steamapps/common/<installdir>`
+    pub install_dir: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct SteamLibraries {
+    /// The `steamapps` folder of every library
+    steamapps: Vec<PathBuf>,
+}
+
+impl SteamLibraries {
+    pub fn discover() -> TResult<Self> {
+        let steam_dir = find_steam_dir().ok_or(Error::SteamNotFound)?;
+        Self::from_steam_dir(&steam_dir)
+    }
+
+    /// Reads the libraries from `<steam>/steamapps/libraryfolders.vdf`. The Steam folder is
+    /// always a library of its own, even if that file somehow doesn't say so.
+    pub fn from_steam_dir(steam_dir: &Path) -> TResult<Self> {
+        let mut libraries = vec![steam_dir.to_path_buf()];
+
+        let library_folders = steam_dir.join("steamapps").join("libraryfolders.vdf");
+        if library_folders.exists() {
+            let vdf = std::fs::read_to_string(&library_folders)?;
+            libraries
+                .extend(parse_library_folders(&vdf).ok_or(Error::InvalidVdf(library_folders))?);
+        }
+
+        Ok(Self::new(libraries))
+    }
+
+    pub fn new(libraries: Vec<PathBuf>) -> Self {
+        let mut steamapps: Vec<PathBuf> = Vec::new();
+        for library in libraries {
+            let library = library.join("steamapps");
+            if !steamapps.contains(&library) {
+                steamapps.push(library);
+            }
+        }
+
+        SteamLibraries { steamapps }
+    }
+
+    /// Looks for the game's app manifest in every library, the first one with it wins
+    pub fn find_game(&self, game: Game) -> Option<InstalledApp> {
+        let manifest_name = format!("appmanifest_{}.acf", game as u32);
+
+        self.steamapps.iter().find_map(|steamapps| {
+            let manifest = std::fs::read_to_string(steamapps.join(&manifest_name)).ok()?;
+            let manifest = AppManifest::parse(&manifest)?;
+            let install_dir = steamapps
+                .join("common")
+                .join(manifest.install_dir.as_deref()?);
+
+            Some(InstalledApp {
+                manifest,
+                install_dir,
+            })
+        })
+    }
+}
+
+/// Pulls the library paths out of `libraryfolders.vdf`. Newer Steam versions nest them as
+/// `"0" { "path" "..." }`, older ones just went `"1" "..."`.
+pub fn parse_library_folders(vdf: &str) -> Option<Vec<PathBuf>> {
+    let vdf = Vdf::parse(vdf)?;
+    let folders = vdf.get("libraryfolders")?;
+
+    let libraries = folders
+        .entries()
+        .filter(|(key, _)| key.chars().all(|c| c.is_ascii_digit()))
+        .filter_map(|(_, library)| match library {
+            Vdf::Value(path) => Some(path.as_str()),
+            library => library.get_str("path"),
+        })
+        .map(PathBuf::from)
+        .collect();
+
+    Some(libraries)
+}
+
+#[cfg(windows)]
+pub fn find_steam_dir() -> Option<PathBuf> {
+    use windows::{
+        Win32::System::Registry::{HKEY_CURRENT_USER, RRF_RT_REG_SZ, RegGetValueW},
+        core::w,
+    };
+
+    let mut buffer = [0u16; 1024];
+    let mut size = std::mem::size_of_val(&buffer) as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!("Software\\Valve\\Steam"),
+            w!("SteamPath"),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr().cast()),
+            Some(&mut size),
+        )
+    };
+
+    let from_registry = result.is_ok().then(|| {
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        PathBuf::from(String::from_utf16_lossy(&buffer[..len]))
+    });
+
+    from_registry
+        .into_iter()
+        .chain(std::env::var_os("ProgramFiles(x86)").map(|dir| PathBuf::from(dir).join("Steam")))
+        .find(|dir| dir.join("steamapps").exists())
+}
+
+#[cfg(not(windows))]
+pub fn find_steam_dir() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+
+    [
+        home.join(".steam").join("steam"),
+        home.join(".local").join("share").join("Steam"),
+        // flatpak
+        home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+    ]
+    .into_iter()
+    .find(|dir| dir.join("steamapps").exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIBRARY_FOLDERS: &str = include_str!("../../tests/fixtures/steam/libraryfolders.vdf");
+    const LEGACY_LIBRARY_FOLDERS: &str =
+        include_str!("../../tests/fixtures/steam/libraryfolders_legacy.vdf");
+    const ETS2_MANIFEST: &str = include_str!("../../tests/fixtures/steam/appmanifest_227300.acf");
+    const ATS_MANIFEST: &str = include_str!("../../tests/fixtures/steam/appmanifest_270880.acf");
+
+    /// Lays out a Steam folder with a second library next to it, like a real install would
+    fn fake_steam(name: &str) -> (PathBuf, PathBuf) {
+        let root =
+            std::env::temp_dir().join(format!("truckersmp-cli-test-{name}-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&root);
+
+        let steam_dir = root.join("Steam");
+        let library = root.join("SteamLibrary");
+        std::fs::create_dir_all(steam_dir.join("steamapps")).unwrap();
+        std::fs::create_dir_all(library.join("steamapps")).unwrap();
+
+        // backslashes are escaped in vdf files
+        let escape = |path: &Path| path.display().to_string().replace('\\', "\\\\");
+        let library_folders = LIBRARY_FOLDERS
+            .replace("{steam}", &escape(&steam_dir))
+            .replace("{library}", &escape(&library));
+        std::fs::write(
+            steam_dir.join("steamapps").join("libraryfolders.vdf"),
+            library_folders,
+        )
+        .unwrap();
+
+        (root, steam_dir)
+    }
+
+    #[test]
+    fn parses_library_folders() {
+        let libraries = parse_library_folders(LIBRARY_FOLDERS).unwrap();
+        assert_eq!(
+            libraries,
+            [PathBuf::from("{steam}"), PathBuf::from("{library}")]
+        );
+    }
+
+    #[test]
+    fn parses_legacy_library_folders() {
+        let libraries = parse_library_folders(LEGACY_LIBRARY_FOLDERS).unwrap();
+        assert_eq!(
+            libraries,
+            [
+                PathBuf::from("D:\\SteamLibrary"),
+                PathBuf::from("E:\\Games\\Steam")
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_app_manifest() {
+        let manifest = AppManifest::parse(ETS2_MANIFEST).unwrap();
+        assert_eq!(manifest.build_id, Some(15467032));
+        assert_eq!(manifest.beta_key.as_deref(), Some("temporary_1_53"));
+        assert_eq!(
+            manifest.install_dir.as_deref(),
+            Some("Euro Truck Simulator 2")
+        );
+        assert!(manifest.is_fully_installed());
+
+        let manifest = AppManifest::parse(ATS_MANIFEST).unwrap();
+        assert_eq!(manifest.beta_key, None);
+        assert!(!manifest.is_fully_installed());
+    }
+
+    #[test]
+    fn rejects_broken_vdf() {
+        assert!(parse_library_folders("\"libraryfolders\" {").is_none());
+        assert!(AppManifest::parse("\"AppState\" { \"appid\" }").is_none());
+    }
+
+    #[test]
+    fn finds_games_across_libraries() {
+        let (root, steam_dir) = fake_steam("libraries");
+        std::fs::write(
+            root.join("SteamLibrary/steamapps/appmanifest_227300.acf"),
+            ETS2_MANIFEST,
+        )
+        .unwrap();
+        std::fs::write(
+            steam_dir.join("steamapps/appmanifest_270880.acf"),
+            ATS_MANIFEST,
+        )
+        .unwrap();
+
+        let libraries = SteamLibraries::from_steam_dir(&steam_dir).unwrap();
+        // the steam folder is listed in libraryfolders.vdf too, it shouldn't be there twice
+        assert_eq!(libraries.steamapps.len(), 2);
+
+        let ets2 = libraries.find_game(Game::ETS2).unwrap();
+        assert_eq!(
+            ets2.install_dir,
+            root.join("SteamLibrary/steamapps/common/Euro Truck Simulator 2")
+        );
+
+        let ats = libraries.find_game(Game::ATS).unwrap();
+        assert!(!ats.manifest.is_fully_installed());
+
+        _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn finds_nothing_without_manifests() {
+        let (root, steam_dir) = fake_steam("empty");
+
+        let libraries = SteamLibraries::from_steam_dir(&steam_dir).unwrap();
+        assert!(libraries.find_game(Game::ETS2).is_none());
+        assert!(libraries.find_game(Game::ATS).is_none());
+
+        _ = std::fs::remove_dir_all(root);
+    }
+}
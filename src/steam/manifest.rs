@@ -0,0 +1,73 @@
+use std::path::{Path, PathBuf};
+
+use tokio::fs;
+
+use crate::{
+    errors::{Error, TResult},
+    game::Game,
+    steam::vdf::Vdf,
+};
+
+// StateFlags bit Steam sets once every file of the app is on disk
+const STATE_FULLY_INSTALLED: u32 = 4;
+
+/// The bits of `appmanifest_<appid>.acf` we care about
+#[derive(Debug, Clone)]
+pub struct AppManifest {
+    pub build_id: Option<u64>,
+    /// The beta branch the game is on, `None` being the default public branch
+    pub beta_key: Option<String>,
+    /// The game's folder name under `steamapps/common`
+    pub install_dir: Option<String>,
+    pub state_flags: u32,
+}
+
+impl AppManifest {
+    pub fn parse(manifest: &str) -> Option<Self> {
+        let vdf = Vdf::parse(manifest)?;
+        let app_state = vdf.get("AppState")?;
+
+        // MountedConfig is what's actually installed, UserConfig is what was picked in the
+        // properties window and might still be downloading
+        let beta_key = ["MountedConfig", "UserConfig"]
+            .into_iter()
+            .find_map(|config| app_state.get(config)?.get_str("BetaKey"))
+            .filter(|key| !key.is_empty() && *key != "public")
+            .map(String::from);
+
+        Some(AppManifest {
+            build_id: app_state.get_str("buildid").and_then(|id| id.parse().ok()),
+            beta_key,
+            install_dir: app_state.get_str("installdir").map(String::from),
+            state_flags: app_state
+                .get_str("StateFlags")
+                .and_then(|flags| flags.parse().ok())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Half downloaded or waiting on an update doesn't count
+    pub fn is_fully_installed(&self) -> bool {
+        self.state_flags & STATE_FULLY_INSTALLED != 0
+    }
+}
+
+/// `<library>/steamapps/common/<game>/bin/win_x64/<exe>` -> `<library>/steamapps`
+pub fn get_steamapps_dir(game_path: &Path) -> Option<&Path> {
+    game_path.ancestors().nth(5)
+}
+
+/// `<library>/steamapps/appmanifest_<appid>.acf`
+pub fn get_app_manifest_path(game: Game, game_path: &Path) -> Option<PathBuf> {
+    let steamapps = get_steamapps_dir(game_path)?;
+    Some(steamapps.join(format!("appmanifest_{}.acf", game as u32)))
+}
+
+pub async fn read_app_manifest(game: Game, game_path: &Path) -> TResult<AppManifest> {
+    let path = get_app_manifest_path(game, game_path).ok_or(Error::NoAppManifest(game))?;
+    if !path.exists() {
+        return Err(Error::NoAppManifest(game));
+    }
+
+    AppManifest::parse(&fs::read_to_string(&path).await?).ok_or(Error::InvalidVdf(path))
+}
@@ -0,0 +1,5 @@
+//! Reading Steam's own files, for everything the Steamworks API won't tell us
+
+pub mod library;
+pub mod manifest;
+pub mod vdf;
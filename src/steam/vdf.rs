@@ -0,0 +1,123 @@
+//! A parser for Valve's KeyValues text format, which is what `.acf` and `.vdf` files are
+
+use std::{iter::Peekable, str::Chars};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Vdf {
+    Value(String),
+    Object(Vec<(String, Vdf)>),
+}
+
+enum Token {
+    String(String),
+    Open,
+    Close,
+}
+
+impl Vdf {
+    /// Parses a whole file, the top level keys end up in the returned object
+    pub fn parse(input: &str) -> Option<Vdf> {
+        let mut tokens = Tokens {
+            chars: input.chars().peekable(),
+        };
+        let root = parse_object(&mut tokens, true)?;
+
+        Some(Vdf::Object(root))
+    }
+
+    /// Steam isn't consistent with the casing of its keys, so neither are we
+    pub fn get(&self, key: &str) -> Option<&Vdf> {
+        self.entries()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Vdf::as_str)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Vdf::Value(value) => Some(value),
+            Vdf::Object(_) => None,
+        }
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &(String, Vdf)> {
+        match self {
+            Vdf::Object(entries) => entries.iter(),
+            Vdf::Value(_) => [].iter(),
+        }
+    }
+}
+
+fn parse_object(tokens: &mut Tokens, root: bool) -> Option<Vec<(String, Vdf)>> {
+    let mut entries = Vec::new();
+
+    loop {
+        let key = match tokens.next() {
+            Some(Token::String(key)) => key,
+            Some(Token::Close) if !root => return Some(entries),
+            None if root => return Some(entries),
+            _ => return None,
+        };
+
+        let value = match tokens.next()? {
+            Token::String(value) => Vdf::Value(value),
+            Token::Open => Vdf::Object(parse_object(tokens, false)?),
+            Token::Close => return None,
+        };
+        entries.push((key, value));
+    }
+}
+
+struct Tokens<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        loop {
+            match self.chars.next()? {
+                c if c.is_whitespace() => continue,
+                '/' if self.chars.peek() == Some(&'/') => {
+                    self.chars.by_ref().find(|&c| c == '\n');
+                }
+                // platform conditionals like [$WIN32], nothing we care about uses them
+                '[' => {
+                    self.chars.by_ref().find(|&c| c == ']');
+                }
+                '{' => return Some(Token::Open),
+                '}' => return Some(Token::Close),
+                '"' => {
+                    let mut string = String::new();
+                    while let Some(c) = self.chars.next() {
+                        match c {
+                            '"' => return Some(Token::String(string)),
+                            '\\' => match self.chars.next()? {
+                                'n' => string.push('\n'),
+                                't' => string.push('\t'),
+                                c => string.push(c),
+                            },
+                            c => string.push(c),
+                        }
+                    }
+                    return None;
+                }
+                c => {
+                    let mut string = String::from(c);
+                    while let Some(&c) = self.chars.peek() {
+                        if c.is_whitespace() || matches!(c, '"' | '{' | '}') {
+                            break;
+                        }
+                        string.push(c);
+                        self.chars.next();
+                    }
+                    return Some(Token::String(string));
+                }
+            }
+        }
+    }
+}